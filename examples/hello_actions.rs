@@ -0,0 +1,55 @@
+use breeze::prelude::*;
+
+const BINDINGS_FILE: &str = "bindings.cfg";
+
+#[derive(Default)]
+struct MyGame {
+    pos: Vec2,
+    jumps: u32,
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, ctx: &mut Context) {
+        // Use the player's saved controls if there are any
+        if ctx.input.load_bindings(BINDINGS_FILE).is_err() {
+            ctx.input.bind_action("jump", [KeyCode::Space.into(), GamepadButton::South.into()]);
+            ctx.input.bind_axis("move_x", [AxisBinding::keys(KeyCode::KeyA, KeyCode::KeyD), GamepadAxis::LeftStickX.into()]);
+            ctx.input.bind_axis("move_y", [AxisBinding::keys(KeyCode::KeyS, KeyCode::KeyW), GamepadAxis::LeftStickY.into()]);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        let speed = 200.0 * ctx.time.delta_secs();
+        self.pos += vec2(ctx.input.axis("move_x"), ctx.input.axis("move_y")) * speed;
+
+        if ctx.input.action_pressed("jump") {
+            self.jumps += 1;
+        }
+
+        // Remap jump to Enter and remember it for next time
+        if ctx.input.key_pressed(KeyCode::KeyR) {
+            ctx.input.bind_action("jump", [KeyCode::Enter.into(), GamepadButton::South.into()]);
+            let _ = ctx.input.save_bindings(BINDINGS_FILE);
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(LIGHT_STEEL_BLUE));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+            world.draw2d.circle(self.pos, 30.0, None, Color::from(RED));
+            world.text.draw(format!("Jumps: {}", self.jumps), vec2(0.0, 250.0));
+            world.text.draw("WASD or left stick to move, Space/South to jump, R to rebind jump to Enter", vec2(0.0, -250.0));
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Actions!")
+        .resolution(800, 600)
+        .run(MyGame::default());
+}
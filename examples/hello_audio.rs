@@ -1,7 +1,9 @@
 use breeze::prelude::*;
 
 #[derive(Default)]
-struct MyGame;
+struct MyGame {
+    seed: u64,
+}
 
 impl Scene for MyGame {
     fn update(&mut self, ctx: &mut Context) -> SceneTransition {
@@ -12,6 +14,27 @@ impl Scene for MyGame {
             // License: (Creative Commons Zero, CC0)
             ctx.audio.play_vol("switch_001.ogg", 0.2);
         }
+
+        // Panned and pitched variations of the same sound
+        if ctx.input.key_pressed(KeyCode::ArrowLeft) {
+            ctx.audio.sound("switch_001.ogg").volume(0.2).pan(-1.0).pitch(0.8).play();
+        }
+        if ctx.input.key_pressed(KeyCode::ArrowRight) {
+            ctx.audio.sound("switch_001.ogg").volume(0.2).pan(1.0).pitch(1.25).play();
+        }
+
+        // Synthesized sounds, no files needed. A new seed gives a new variation each time.
+        if ctx.input.key_pressed(KeyCode::Digit1) {
+            ctx.audio.play_tone(440.0, 0.25, Waveform::Square);
+        }
+        if ctx.input.key_pressed(KeyCode::Digit2) {
+            self.seed += 1;
+            ctx.audio.play_sfx(&SfxParams::pickup(self.seed));
+        }
+        if ctx.input.key_pressed(KeyCode::Digit3) {
+            self.seed += 1;
+            ctx.audio.play_sfx(&SfxParams::explosion(self.seed));
+        }
         SceneTransition::None
     }
 
@@ -21,6 +44,8 @@ impl Scene for MyGame {
         ctx.with_layer(0, |ui| {
             ui.set_camera(CameraMode::default());
             ui.text.draw("Press Space to Play Sound Effect", vec2(-150.0, 0.0));
+            ui.text.draw("Left/Right to Play it Panned", vec2(-150.0, -40.0));
+            ui.text.draw("1: Tone, 2: Pickup, 3: Explosion", vec2(-150.0, -80.0));
         });
     }
 }
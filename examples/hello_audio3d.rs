@@ -0,0 +1,63 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    angle: f32,
+}
+
+impl MyGame {
+    fn speaker_position(&self) -> Vec3 {
+        vec3(self.angle.cos() * 6.0, 0.5, self.angle.sin() * 6.0)
+    }
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, ctx: &mut Context) {
+        // Celebration by Kamye (from looperman.com)
+        ctx.audio.play_loop_at("speaker", "celebrate.ogg", self.speaker_position());
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        // Circle the speaker around the camera, which is the listener
+        self.angle += ctx.time.delta_secs() * 0.5;
+        ctx.audio.set_position("speaker", self.speaker_position());
+
+        // Click the ground to play a one-shot sound there
+        if ctx.input.mouse_pressed(MouseButton::Left) {
+            let hit = ctx.input.mouse_ray(0)
+                .and_then(|ray| ray_plane_intersection(ray, Vec3::ZERO, Vec3::Y));
+            if let Some(point) = hit {
+                ctx.audio.play_at("switch_001.ogg", point);
+            }
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(BLACK));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::Camera3d {
+                position: Vec3::new(0.0, 4.0, 4.0),
+                target: Vec3::ZERO,
+            });
+
+            world.lights.directional(Vec3::new(-1.0, -2.0, -1.0), Color::WHITE, 5_000.0, false);
+            world.draw3d.plane(Vec3::ZERO, Quat::IDENTITY, 20.0, None, Color::from(DARK_GREEN));
+            world.draw3d.cube(self.speaker_position(), Quat::IDENTITY, 1.0, None, Color::from(ORANGE_RED));
+        });
+
+        ctx.with_layer(1, |ui| {
+            ui.set_camera(CameraMode::default());
+            ui.text.draw_ext("The music follows the cube. Click the ground for a sound there.", vec2(0.0, 300.0), 24.0, Color::WHITE);
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, 3D Audio!")
+        .resolution(1280, 720)
+        .run(MyGame::default());
+}
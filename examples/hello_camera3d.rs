@@ -0,0 +1,85 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+enum View {
+    #[default]
+    Orbit,
+    Fly,
+    Isometric,
+}
+
+#[derive(Default)]
+struct MyGame {
+    view: View,
+    orbit: OrbitController,
+    fly: FlyController,
+    lens: CameraLens,
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        if ctx.input.key_pressed(KeyCode::Tab) {
+            self.view = match self.view {
+                View::Orbit => View::Fly,
+                View::Fly => View::Isometric,
+                View::Isometric => View::Orbit,
+            };
+        }
+
+        match self.view {
+            View::Orbit => self.orbit.update(&ctx.input),
+            View::Fly => self.fly.update(&ctx.input, ctx.time.delta_secs()),
+            View::Isometric => {}
+        }
+
+        // Z/X to Widen/Narrow the Field of View
+        if ctx.input.key_down(KeyCode::KeyZ) {
+            self.lens.fov = (self.lens.fov + ctx.time.delta_secs()).min(2.5);
+        }
+        if ctx.input.key_down(KeyCode::KeyX) {
+            self.lens.fov = (self.lens.fov - ctx.time.delta_secs()).max(0.2);
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(BLACK));
+
+        ctx.with_layer(0, |world| {
+            world.set_lens(self.lens);
+            world.set_camera(match self.view {
+                View::Orbit => self.orbit.camera_mode(),
+                View::Fly => self.fly.camera_mode(),
+                View::Isometric => CameraMode::Orthographic3d {
+                    position: Vec3::new(10.0, 10.0, 10.0),
+                    target: Vec3::ZERO,
+                    height: 8.0,
+                },
+            });
+
+            world.lights.directional(Vec3::new(-1.0, -2.0, -1.0), Color::WHITE, 5_000.0, false);
+            world.draw3d.plane(Vec3::ZERO, Quat::IDENTITY, 10.0, None, Color::from(DARK_GREEN));
+            world.draw3d.cube(Vec3::new(0.0, 0.5, 0.0), Quat::IDENTITY, 1.0, None, Color::from(ORANGE_RED));
+            world.draw3d.sphere(Vec3::new(2.0, 0.5, -1.0), 0.5, None, Color::from(STEEL_BLUE));
+        });
+
+        ctx.with_layer(1, |ui| {
+            ui.set_camera(CameraMode::default());
+            let help = match self.view {
+                View::Orbit => "Orbit: Drag to Turn, Wheel to Zoom",
+                View::Fly => "Fly: Hold Right Mouse to Look, WASD/Q/E to Move",
+                View::Isometric => "Isometric",
+            };
+            ui.text.draw(help, vec2(0.0, 300.0));
+            ui.text.draw("Tab: Next View, Z/X: Field of View", vec2(0.0, 260.0));
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, 3D Cameras!")
+        .resolution(1280, 720)
+        .run(MyGame::default());
+}
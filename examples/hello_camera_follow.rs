@@ -0,0 +1,87 @@
+use breeze::prelude::*;
+
+struct MyGame {
+    player: Vec2,
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, ctx: &mut Context) {
+        let camera = ctx.cameras.camera2d(0);
+        camera.dead_zone = vec2(120.0, 80.0);
+        camera.bounds = Some(Rect::new(-1000.0, -1000.0, 1000.0, 1000.0));
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        let speed = 400.0 * ctx.time.delta_secs();
+
+        if ctx.input.key_down(KeyCode::ArrowRight) {
+            self.player.x += speed;
+        }
+        if ctx.input.key_down(KeyCode::ArrowLeft) {
+            self.player.x -= speed;
+        }
+        if ctx.input.key_down(KeyCode::ArrowUp) {
+            self.player.y += speed;
+        }
+        if ctx.input.key_down(KeyCode::ArrowDown) {
+            self.player.y -= speed;
+        }
+        self.player = self.player.clamp(Vec2::splat(-1000.0), Vec2::splat(1000.0));
+
+        let mouse = ctx.input.mouse_pos();
+        let wheel = ctx.input.mouse_wheel().y;
+        let camera = ctx.cameras.camera2d(0);
+        camera.follow(self.player);
+
+        // Zoom towards the mouse with the wheel
+        if wheel != 0.0 {
+            let scale = camera.scale * 0.9f32.powf(wheel);
+            camera.zoom_to(mouse, scale.clamp(0.25, 2.0));
+        }
+
+        // Q/E to Rotate, Space to Shake
+        if ctx.input.key_down(KeyCode::KeyQ) {
+            camera.rotation += ctx.time.delta_secs();
+        }
+        if ctx.input.key_down(KeyCode::KeyE) {
+            camera.rotation -= ctx.time.delta_secs();
+        }
+        if ctx.input.key_pressed(KeyCode::Space) {
+            camera.add_trauma(0.5);
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(DARK_SLATE_GRAY));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+
+            // A grid of markers. Culling skips the ones the camera can't see.
+            world.set_culling(true);
+            for x in -10..=10 {
+                for y in -10..=10 {
+                    world.draw2d.circle(vec2(x as f32, y as f32) * 100.0, 8.0, None, Color::from(GRAY));
+                }
+            }
+
+            world.draw2d.circle(self.player, 20.0, None, Color::from(ORANGE_RED));
+        });
+
+        ctx.with_layer(1, |ui| {
+            ui.set_camera(CameraMode::default());
+            ui.text.draw("Arrows: Move, Wheel: Zoom, Q/E: Rotate, Space: Shake", vec2(0.0, 250.0));
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Camera Follow!")
+        .resolution(800, 600)
+        .run(MyGame {
+            player: Vec2::ZERO,
+        });
+}
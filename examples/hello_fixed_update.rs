@@ -0,0 +1,45 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    previous: Vec2,
+    current: Vec2,
+    velocity: Vec2,
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, _ctx: &mut Context) {
+        self.velocity = vec2(300.0, 0.0);
+    }
+
+    fn fixed_update(&mut self, ctx: &mut Context) {
+        // Runs exactly 30 times per second, whatever the frame rate
+        self.previous = self.current;
+        self.current += self.velocity * ctx.fixed_delta_secs();
+
+        if self.current.x.abs() > 300.0 {
+            self.velocity.x = -self.velocity.x;
+        }
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(DARK_SLATE_GRAY));
+
+        // Blend between the last two fixed states for smooth movement
+        let pos = self.previous.lerp(self.current, ctx.alpha());
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+            world.draw2d.circle(pos, 30.0, None, Color::from(GOLD));
+            world.text.draw_ext("Moving at a fixed 30 Hz", vec2(0.0, 200.0), 24.0, Color::WHITE);
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Fixed Update!")
+        .resolution(800, 600)
+        .fixed_timestep(30.0)
+        .run(MyGame::default());
+}
@@ -0,0 +1,49 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    players: Vec<(GamepadId, Vec2)>,
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        // Give each new pad its own circle, and forget pads that are unplugged
+        for &id in ctx.input.gamepads_connected() {
+            self.players.push((id, Vec2::ZERO));
+        }
+        for id in ctx.input.gamepads_disconnected() {
+            self.players.retain(|(player, _)| player != id);
+        }
+
+        let speed = 300.0 * ctx.time.delta_secs();
+        for (id, pos) in self.players.iter_mut() {
+            *pos += ctx.input.left_stick(*id) * speed;
+
+            if ctx.input.gamepad_button_pressed(*id, GamepadButton::South) {
+                *pos = Vec2::ZERO;
+            }
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(DARK_SLATE_GRAY));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+            for (_, pos) in &self.players {
+                world.draw2d.circle(*pos, 30.0, None, Color::from(ORANGE));
+            }
+            world.text.draw_ext("Connect a gamepad. Left stick to move, South to reset.", vec2(0.0, 250.0), 20.0, Color::WHITE);
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Gamepad!")
+        .resolution(800, 600)
+        .gamepad_deadzones(0.2, 0.05)
+        .run(MyGame::default());
+}
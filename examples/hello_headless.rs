@@ -0,0 +1,37 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct Counter {
+    ticks: u32,
+}
+
+impl Scene for Counter {
+    fn update(&mut self, _ctx: &mut Context) -> SceneTransition {
+        self.ticks += 1;
+
+        // Quit early once we have seen enough frames
+        if self.ticks == 30 {
+            return SceneTransition::Quit;
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+            world.draw2d.circle(Vec2::ZERO, 30.0, None, Color::from(RED));
+        });
+    }
+}
+
+fn main() {
+    // No window or GPU is needed, so this runs on CI machines
+    let manager = Breeze::default()
+        .title("Hello, Headless!")
+        .run_headless(Counter::default(), 100);
+
+    let counter = manager.current::<Counter>().expect("Counter is still the active scene");
+    assert!(manager.is_quitting());
+    println!("Ran {} frames before quitting", counter.ticks);
+}
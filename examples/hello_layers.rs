@@ -0,0 +1,55 @@
+use breeze::prelude::*;
+
+/// Layer ids are just names; the settings decide what draws on top
+const UI: usize = 0;
+const WORLD: usize = 1;
+
+#[derive(Default)]
+struct MyGame {
+    angle: f32,
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        self.angle += ctx.time.delta_secs();
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.with_layer(WORLD, |world| {
+            // The world draws first, over its own background
+            world.set_settings(LayerSettings {
+                order: Some(0),
+                clear: LayerClear::Color(Color::from(MIDNIGHT_BLUE)),
+                msaa: Msaa::Sample8,
+                hdr: true,
+                tonemapping: Some(Tonemapping::TonyMcMapface),
+            });
+            world.set_camera(CameraMode::Camera3d {
+                position: Vec3::new(0.0, 3.0, 6.0),
+                target: Vec3::ZERO,
+            });
+
+            world.lights.directional(Vec3::new(-1.0, -2.0, -1.0), Color::WHITE, 5_000.0, false);
+            world.draw3d.cube(Vec3::ZERO, Quat::from_rotation_y(self.angle), 2.0, None, Color::from(ORANGE_RED));
+        });
+
+        ctx.with_layer(UI, |ui| {
+            // The UI draws last and keeps everything below it
+            ui.set_settings(LayerSettings {
+                order: Some(10),
+                clear: LayerClear::Nothing,
+                ..LayerSettings::default()
+            });
+            ui.set_camera(CameraMode::default());
+            ui.text.draw("Layer 0 is drawn over layer 1", vec2(0.0, 300.0));
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Layers!")
+        .resolution(1280, 720)
+        .run(MyGame::default());
+}
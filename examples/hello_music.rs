@@ -10,20 +10,32 @@ impl Scene for MyGame {
     fn init(&mut self, ctx: &mut Context) {
         // Celebration by Kamye (from looperman.com)
         ctx.audio.play_loop_vol("music", "celebrate.ogg", 0.5);
-        self.music_playing = true;
     }
 
     fn update(&mut self, ctx: &mut Context) -> SceneTransition {
-        // Pause music when space is pressed
+        // Pause music when P is pressed
         if ctx.input.key_pressed(KeyCode::KeyP) {
-            if !self.music_playing {
+            if ctx.audio.is_paused("music") {
                 ctx.audio.resume("music");
-                self.music_playing = true;
             } else {
                 ctx.audio.pause("music");
-                self.music_playing = false;
             }
         }
+        self.music_playing = ctx.audio.is_playing("music");
+
+        // Music bus volume and mute, applied to the loop while it plays
+        if ctx.input.key_pressed(KeyCode::ArrowUp) {
+            let volume = ctx.audio.bus_volume(AudioBus::Music);
+            ctx.audio.set_bus_volume(AudioBus::Music, (volume + 0.1).min(1.0));
+        }
+        if ctx.input.key_pressed(KeyCode::ArrowDown) {
+            let volume = ctx.audio.bus_volume(AudioBus::Music);
+            ctx.audio.set_bus_volume(AudioBus::Music, (volume - 0.1).max(0.0));
+        }
+        if ctx.input.key_pressed(KeyCode::KeyM) {
+            let muted = ctx.audio.is_bus_muted(AudioBus::Music);
+            ctx.audio.set_bus_muted(AudioBus::Music, !muted);
+        }
 
         SceneTransition::None
     }
@@ -39,6 +51,7 @@ impl Scene for MyGame {
             } else {
                 ui.text.draw("Music Paused - Press 'P' to Resume", vec2(0.0, 0.0));
             }
+            ui.text.draw("Up/Down: Music Volume, M: Mute", vec2(0.0, -40.0));
         });
     }
 }
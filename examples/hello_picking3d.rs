@@ -0,0 +1,72 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    hover: Option<Vec3>,
+    markers: Vec<Vec3>,
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        // Find the point on the ground (y = 0) under the mouse
+        self.hover = ctx.input.mouse_ray(0)
+            .and_then(|ray| ray_plane_intersection(ray, Vec3::ZERO, Vec3::Y));
+
+        if ctx.input.mouse_pressed(MouseButton::Left) {
+            if let Some(point) = self.hover {
+                // Snap to the 1x1 grid
+                self.markers.push(vec3(point.x.round(), 0.5, point.z.round()));
+            }
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(BLACK));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::Camera3d {
+                position: Vec3::new(0.0, 10.0, 12.0),
+                target: Vec3::ZERO,
+            });
+
+            world.lights.directional(Vec3::new(-1.0, -2.0, -1.0), Color::WHITE, 5_000.0, false);
+            world.draw3d.plane(Vec3::ZERO, Quat::IDENTITY, 20.0, None, Color::from(DARK_GREEN));
+
+            if let Some(point) = self.hover {
+                world.draw3d.sphere(point, 0.2, None, Color::from(YELLOW));
+            }
+
+            // Skip cubes outside the camera's view
+            let visible = |marker: &&Vec3| world.view.is_none_or(|view| view.is_sphere_visible(**marker, 1.0));
+            for marker in self.markers.iter().filter(visible) {
+                world.draw3d.cube(*marker, Quat::IDENTITY, 1.0, None, Color::from(ORANGE_RED));
+            }
+        });
+
+        // Label each cube on the UI layer, above where it appears on screen
+        let labels: Vec<(usize, Vec2)> = self.markers.iter().enumerate()
+            .filter_map(|(i, marker)| {
+                let screen = ctx.views.world_to_screen(0, *marker + Vec3::Y)?;
+                Some((i + 1, ctx.views.screen_to_world(1, screen)?))
+            })
+            .collect();
+
+        ctx.with_layer(1, |ui| {
+            ui.set_camera(CameraMode::default());
+            ui.text.draw_ext("Click the ground to place a cube", vec2(0.0, 300.0), 24.0, Color::WHITE);
+
+            for (number, pos) in labels {
+                ui.text.draw_ext(number.to_string(), pos, 20.0, Color::WHITE);
+            }
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, 3D Picking!")
+        .resolution(1280, 720)
+        .run(MyGame::default());
+}
@@ -0,0 +1,47 @@
+use breeze::prelude::*;
+
+/// The resolution the game is drawn at
+const LOW_RES: (u32, u32) = (160, 90);
+
+#[derive(Default)]
+struct MyGame {
+    screen: ImageAsset,
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, ctx: &mut Context) {
+        self.screen = ctx.create_render_texture(LOW_RES.0, LOW_RES.1);
+    }
+
+    fn update(&mut self, _ctx: &mut Context) -> SceneTransition {
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(MIDNIGHT_BLUE));
+        let scale = (ctx.screen_size() / vec2(LOW_RES.0 as f32, LOW_RES.1 as f32)).min_element().floor().max(1.0);
+        let x_pos = ctx.time.elapsed_secs().sin() * 60.0;
+
+        // Render textures are drawn before the window, whatever their layer number
+        ctx.with_layer(1, |low_res| {
+            low_res.set_camera(CameraMode::default());
+            low_res.set_render_target(Some(self.screen.clone()));
+
+            low_res.draw2d.circle(vec2(x_pos, 0.0), 12.0, None, Color::from(ORANGE_RED));
+            low_res.draw2d.rect(vec2(0.0, -35.0), vec2(160.0, 20.0), None, Color::from(DARK_GREEN));
+        });
+
+        // Show it scaled up with hard pixel edges
+        ctx.with_layer(0, |window| {
+            window.set_camera(CameraMode::default());
+            window.sprites.draw_ext(&self.screen, 0.0, 0.0, scale, Color::WHITE);
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Render Texture!")
+        .resolution(1280, 720)
+        .run(MyGame::default());
+}
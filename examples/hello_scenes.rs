@@ -8,7 +8,10 @@ struct TitleScreen;
 impl Scene for TitleScreen {
     fn update(&mut self, ctx: &mut Context) -> SceneTransition {
         if ctx.input.key_pressed(KeyCode::Enter) {
-            return SceneTransition::Switch(Box::new(GameScreen::default()));
+            return SceneTransition::SwitchWith(
+                Box::new(GameScreen::default()),
+                Transition::Fade { color: Color::BLACK, secs: 1.0 },
+            );
         }
         SceneTransition::None
     }
@@ -32,6 +35,7 @@ impl Scene for TitleScreen {
 struct GameScreen {
     player_pos: Vec2,
     player_sprite: ImageAsset,
+    quit_requested: bool,
 }
 
 impl Scene for GameScreen {
@@ -39,12 +43,23 @@ impl Scene for GameScreen {
         self.player_sprite = ctx.load_image("breeze.png");
     }
 
+    fn on_resume(&mut self, _ctx: &mut Context, result: Option<SceneResult>) {
+        // The pause screen tells us what the player chose
+        if let Some(Ok(PauseChoice::Quit)) = result.map(|r| r.downcast::<PauseChoice>()) {
+            self.quit_requested = true;
+        }
+    }
+
     fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        if self.quit_requested {
+            return SceneTransition::Switch(Box::new(TitleScreen));
+        }
+
         if ctx.input.key_down(KeyCode::ArrowRight) { self.player_pos.x += 5.0; }
         if ctx.input.key_down(KeyCode::ArrowLeft)  { self.player_pos.x -= 5.0; }
 
         if ctx.input.key_pressed(KeyCode::Space) {
-            return SceneTransition::Push(Box::new(PauseScreen));
+            return SceneTransition::PushWith(Box::new(PauseScreen), Transition::Crossfade { secs: 0.25 });
         }
 
         SceneTransition::None
@@ -67,6 +82,11 @@ impl Scene for GameScreen {
             ui.text.draw("Press SPACE to Pause", Vec2::new(0.0, -200.0));
         });
     }
+
+    // The game fills the screen, so nothing beneath it needs drawing
+    fn is_opaque(&self) -> bool {
+        true
+    }
 }
 
 // ============================================================================
@@ -74,10 +94,17 @@ impl Scene for GameScreen {
 // ============================================================================
 struct PauseScreen;
 
+enum PauseChoice {
+    Quit,
+}
+
 impl Scene for PauseScreen {
     fn update(&mut self, ctx: &mut Context) -> SceneTransition {
         if ctx.input.key_pressed(KeyCode::Space) {
-            return SceneTransition::Pop;
+            return SceneTransition::PopWithTransition(Transition::Crossfade { secs: 0.25 });
+        }
+        if ctx.input.key_pressed(KeyCode::KeyQ) {
+            return SceneTransition::pop_with(PauseChoice::Quit);
         }
         SceneTransition::None
     }
@@ -92,6 +119,7 @@ impl Scene for PauseScreen {
             // Semi-transparent black box
             overlay.draw2d.rect(Vec2::ZERO, Vec2::new(1280.0, 720.0), None, Color::srgba(0.0, 0.0, 0.0, 0.7));
             overlay.text.draw("PAUSED", Vec2::ZERO);
+            overlay.text.draw("Press Q to return to the title screen", Vec2::new(0.0, -50.0));
         });
     }
 }
@@ -0,0 +1,82 @@
+use breeze::prelude::*;
+
+struct MyGame {
+    players: [Vec2; 2],
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        let speed = 300.0 * ctx.time.delta_secs();
+        let controls = [
+            [KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD],
+            [KeyCode::ArrowUp, KeyCode::ArrowDown, KeyCode::ArrowLeft, KeyCode::ArrowRight],
+        ];
+
+        for (player, [up, down, left, right]) in self.players.iter_mut().zip(controls) {
+            if ctx.input.key_down(up) {
+                player.y += speed;
+            }
+            if ctx.input.key_down(down) {
+                player.y -= speed;
+            }
+            if ctx.input.key_down(left) {
+                player.x -= speed;
+            }
+            if ctx.input.key_down(right) {
+                player.x += speed;
+            }
+        }
+
+        // Click to teleport the player whose half the mouse is over
+        if ctx.input.mouse_pressed(MouseButton::Left) {
+            for (layer, player) in self.players.iter_mut().enumerate() {
+                if ctx.input.mouse_over_layer(layer) {
+                    *player = ctx.input.mouse_pos_on_layer(layer).unwrap_or(*player);
+                }
+            }
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(DARK_SLATE_GRAY));
+
+        let halves = [Rect::new(0.0, 0.0, 0.5, 1.0), Rect::new(0.5, 0.0, 1.0, 1.0)];
+        for (layer, half) in halves.into_iter().enumerate() {
+            ctx.with_layer(layer, |view| {
+                // Each half follows its own player
+                view.set_viewport(Some(LayerViewport::Normalized(half)));
+                view.set_camera(CameraMode::Camera2d {
+                    position: self.players[layer],
+                    scale: 1.0,
+                });
+
+                view.draw2d.rect(vec2(0.0, -200.0), vec2(600.0, 40.0), None, Color::from(DARK_GREEN));
+                view.draw2d.circle(self.players[0], 20.0, None, Color::from(ORANGE_RED));
+                view.draw2d.circle(self.players[1], 20.0, None, Color::from(STEEL_BLUE));
+            });
+        }
+
+        // A minimap in the top right corner
+        ctx.with_layer(2, |minimap| {
+            minimap.set_viewport(Some(LayerViewport::Pixels(Rect::new(1060.0, 20.0, 1260.0, 170.0))));
+            minimap.set_camera(CameraMode::Camera2d {
+                position: Vec2::ZERO,
+                scale: 4.0,
+            });
+
+            minimap.draw2d.circle(self.players[0], 40.0, None, Color::from(ORANGE_RED));
+            minimap.draw2d.circle(self.players[1], 40.0, None, Color::from(STEEL_BLUE));
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Split Screen!")
+        .resolution(1280, 720)
+        .run(MyGame {
+            players: [vec2(-100.0, 0.0), vec2(100.0, 0.0)],
+        });
+}
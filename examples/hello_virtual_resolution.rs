@@ -0,0 +1,36 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    cursor: Vec2,
+}
+
+impl Scene for MyGame {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        // Already in virtual pixels, whatever the window size
+        self.cursor = ctx.input.mouse_pos();
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(DARK_SLATE_GRAY));
+
+        ctx.with_layer(0, |world| {
+            world.set_camera(CameraMode::default());
+
+            // The whole 320x180 design area, scaled up by whole multiples
+            world.draw2d.rect(vec2(0.0, -70.0), vec2(320.0, 40.0), None, Color::from(DARK_GREEN));
+            world.draw2d.circle(self.cursor, 4.0, None, Color::from(ORANGE_RED));
+            world.text.draw_ext("Resize the window", vec2(0.0, 60.0), 16.0, Color::WHITE);
+            world.text.draw_ext(format!("{:.0}, {:.0}", self.cursor.x, self.cursor.y), vec2(0.0, 40.0), 16.0, Color::WHITE);
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Virtual Resolution!")
+        .resolution(1280, 720)
+        .virtual_resolution(320, 180, ScaleMode::IntegerScale)
+        .run(MyGame::default());
+}
@@ -2,6 +2,8 @@ use breeze::prelude::*;
 
 struct MyGame {
     is_fullscreen: bool,
+    vsync: bool,
+    cursor_visible: bool,
 }
 
 impl Scene for MyGame {
@@ -11,6 +13,20 @@ impl Scene for MyGame {
             self.is_fullscreen = !self.is_fullscreen;
             ctx.window.set_fullscreen(self.is_fullscreen);
         }
+
+        if ctx.input.key_pressed(KeyCode::KeyV) {
+            self.vsync = !self.vsync;
+            ctx.window.set_vsync(self.vsync);
+        }
+
+        if ctx.input.key_pressed(KeyCode::KeyC) {
+            self.cursor_visible = !self.cursor_visible;
+            ctx.window.set_cursor_visible(self.cursor_visible);
+        }
+
+        if ctx.input.key_pressed(KeyCode::KeyM) {
+            ctx.window.set_minimized(true);
+        }
         SceneTransition::None
     }
 
@@ -23,6 +39,7 @@ impl Scene for MyGame {
             } else {
                 ui.text.draw("Windowed mode - Press Alt+Enter to toggle", Vec2::ZERO);
             }
+            ui.text.draw(format!("V: vsync ({}), C: cursor, M: minimize", if self.vsync { "on" } else { "off" }), vec2(0.0, -40.0));
         });
     }
 }
@@ -31,7 +48,11 @@ fn main() {
     Breeze::default()
         .title("Breeze example")
         .resolution(640, 480)
+        .min_size(320, 240)
+        .icon("bunny.png")
         .run(MyGame {
             is_fullscreen: false,
+            vsync: true,
+            cursor_visible: true,
         });
 }
\ No newline at end of file
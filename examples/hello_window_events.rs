@@ -0,0 +1,64 @@
+use breeze::prelude::*;
+
+#[derive(Default)]
+struct MyGame {
+    log: Vec<String>,
+    confirm_quit: bool,
+}
+
+impl Scene for MyGame {
+    fn init(&mut self, ctx: &mut Context) {
+        // Ask before quitting instead of closing straight away
+        ctx.window.set_close_veto(true);
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        for event in ctx.window.events() {
+            match event {
+                WindowEvent::CloseRequested => self.confirm_quit = true,
+                WindowEvent::FileDropped(path) => self.log.push(format!("Dropped {}", path.display())),
+                other => self.log.push(format!("{other:?}")),
+            }
+        }
+
+        // Keep the last few lines
+        let excess = self.log.len().saturating_sub(8);
+        self.log.drain(..excess);
+
+        if self.confirm_quit {
+            if ctx.input.key_pressed(KeyCode::KeyY) {
+                return SceneTransition::Quit;
+            }
+            if ctx.input.key_pressed(KeyCode::KeyN) {
+                self.confirm_quit = false;
+            }
+        }
+
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, ctx: &mut DrawContext) {
+        ctx.clear_background(Color::from(MIDNIGHT_BLUE));
+
+        ctx.with_layer(0, |ui| {
+            ui.set_camera(CameraMode::default());
+
+            if self.confirm_quit {
+                ui.text.draw("Really quit? (Y/N)", vec2(0.0, 200.0));
+            } else {
+                ui.text.draw("Resize, focus, drop a file or close the window", vec2(0.0, 200.0));
+            }
+
+            for (i, line) in self.log.iter().enumerate() {
+                ui.text.draw(line.as_str(), vec2(0.0, 120.0 - i as f32 * 30.0));
+            }
+        });
+    }
+}
+
+fn main() {
+    Breeze::default()
+        .title("Hello, Window Events!")
+        .resolution(800, 600)
+        .run(MyGame::default());
+}
@@ -1,6 +1,23 @@
 use bevy::camera::visibility::RenderLayers;
+use bevy::camera::primitives::{Frustum, Sphere};
+use bevy::camera::{Camera3dDepthLoadOp, RenderTarget, ScalingMode, Viewport};
+use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::ecs::query::QueryData;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy::render::view::{Hdr, Msaa};
+use bevy::window::PrimaryWindow;
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+
+use crate::core::input::InputContext;
+use crate::core::transition::TRANSITION_LAYER;
+
+/// Texture cameras render before every window layer, so the window layers can draw their images
+const TEXTURE_ORDER_OFFSET: isize = 1000;
+
+/// Stop just short of straight up or down, where yaw stops making sense
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum CameraMode {
@@ -13,7 +30,26 @@ pub enum CameraMode {
     Camera3d {
         position: Vec3,
         target: Vec3,
-    }
+    },
+    /// Circle around `target`. Angles are in radians; positive pitch looks down from above.
+    Orbit {
+        target: Vec3,
+        yaw: f32,
+        pitch: f32,
+        distance: f32,
+    },
+    /// Look around from `position`. Angles are in radians; yaw 0.0 faces -Z and positive pitch looks up.
+    FirstPerson {
+        position: Vec3,
+        yaw: f32,
+        pitch: f32,
+    },
+    /// A 3D view without perspective, e.g. isometric. `height` is how many world units fit vertically.
+    Orthographic3d {
+        position: Vec3,
+        target: Vec3,
+        height: f32,
+    },
 }
 
 impl Default for CameraMode {
@@ -25,21 +61,945 @@ impl Default for CameraMode {
     }
 }
 
+impl CameraMode {
+    /// Where a 3D mode puts the camera and how it projects, or None for 2D modes.
+    /// `rotation` is kept when the camera stands exactly on its target.
+    fn view_3d(&self, lens: CameraLens, rotation: Quat) -> Option<(Transform, Projection)> {
+        let perspective = Projection::Perspective(PerspectiveProjection {
+            fov: lens.fov,
+            near: lens.near,
+            far: lens.far,
+            ..default()
+        });
+
+        match *self {
+            CameraMode::Camera3d { position, target } => Some((look_at(position, target, rotation), perspective)),
+            CameraMode::Orbit { target, yaw, pitch, distance } => {
+                // The camera's forward (-Z) points back along the offset, straight at the target
+                let rotation = Quat::from_euler(EulerRot::YXZ, yaw, -pitch, 0.0);
+                let transform = Transform::from_translation(target + rotation * Vec3::Z * distance).with_rotation(rotation);
+                Some((transform, perspective))
+            }
+            CameraMode::FirstPerson { position, yaw, pitch } => {
+                let transform = Transform::from_translation(position).with_rotation(Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0));
+                Some((transform, perspective))
+            }
+            CameraMode::Orthographic3d { position, target, height } => {
+                let projection = Projection::Orthographic(OrthographicProjection {
+                    scaling_mode: ScalingMode::FixedVertical { viewport_height: height },
+                    near: lens.near,
+                    far: lens.far,
+                    ..OrthographicProjection::default_3d()
+                });
+                Some((look_at(position, target, rotation), projection))
+            }
+            CameraMode::None | CameraMode::Camera2d { .. } => None,
+        }
+    }
+}
+
+/// A transform at `position` facing `target`, keeping `rotation` if they're the same point
+fn look_at(position: Vec3, target: Vec3, rotation: Quat) -> Transform {
+    let transform = Transform::from_translation(position);
+    if position == target {
+        transform.with_rotation(rotation)
+    } else {
+        transform.looking_at(target, Vec3::Y)
+    }
+}
+
+/// Field of view and clipping distances for a layer's 3D camera
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraLens {
+    /// Vertical field of view in radians (unused by `Orthographic3d`)
+    pub fov: f32,
+    /// Nothing closer than this is drawn
+    pub near: f32,
+    /// Nothing further than this is drawn
+    pub far: f32,
+}
+
+impl Default for CameraLens {
+    fn default() -> Self {
+        Self { fov: FRAC_PI_4, near: 0.1, far: 1000.0 }
+    }
+}
+
+/// What a layer's camera clears before drawing
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LayerClear {
+    /// Layer 0, render texture layers and layers with their own viewport clear to the background color.
+    /// Other layers draw over the layers below.
+    #[default]
+    Auto,
+    /// Clear to the `clear_background` color
+    Background,
+    /// Clear to a color of its own
+    Color(Color),
+    /// Keep the layers below, but draw 3D on top of them instead of sorting against their depth
+    DepthOnly,
+    /// Keep everything, so 3D is hidden behind nearer 3D from the layers below
+    Nothing,
+}
+
+/// How a layer's camera renders. Set with `LayerContext::set_settings`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerSettings {
+    /// Layers with a higher order draw on top, and layers with the same order draw in layer id order.
+    /// None uses the layer id. Layers rendering into images always draw before the window layers,
+    /// and scene transitions always draw over every layer.
+    pub order: Option<isize>,
+    pub clear: LayerClear,
+    pub msaa: Msaa,
+    /// Render with a high dynamic range, e.g. for bloom
+    pub hdr: bool,
+    /// None uses the camera's default: no tonemapping for 2D layers, Bevy's default for 3D layers
+    pub tonemapping: Option<Tonemapping>,
+}
+
+impl Default for LayerSettings {
+    fn default() -> Self {
+        Self {
+            order: None,
+            clear: LayerClear::Auto,
+            msaa: Msaa::default(),
+            hdr: false,
+            tonemapping: None,
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct CameraQueue(pub Vec<(usize, CameraMode)>);
 
+/// Mouse-driven orbit around a point, for model viewers: drag to turn and scroll to zoom.
+/// Call `update` every frame and draw with `camera_mode`.
+#[derive(Clone, Debug)]
+pub struct OrbitController {
+    pub target: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    /// Radians turned per pixel dragged
+    pub sensitivity: f32,
+    /// Fraction of the distance each wheel step zooms
+    pub zoom_speed: f32,
+    /// The button held while dragging
+    pub button: MouseButton,
+}
+
+impl Default for OrbitController {
+    fn default() -> Self {
+        Self {
+            target: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.5,
+            distance: 5.0,
+            min_distance: 0.5,
+            max_distance: 100.0,
+            sensitivity: 0.005,
+            zoom_speed: 0.1,
+            button: MouseButton::Left,
+        }
+    }
+}
+
+impl OrbitController {
+    pub fn new(target: Vec3, distance: f32) -> Self {
+        Self { target, distance, ..default() }
+    }
+
+    /// Turn with the mouse while the button is held, and zoom with the wheel
+    pub fn update(&mut self, input: &InputContext) {
+        if input.mouse_down(self.button) {
+            let delta = input.mouse_delta();
+            self.yaw -= delta.x * self.sensitivity;
+            self.pitch = (self.pitch + delta.y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+
+        let wheel = input.mouse_wheel().y;
+        if wheel != 0.0 {
+            self.distance = (self.distance * (1.0 - self.zoom_speed).powf(wheel)).clamp(self.min_distance, self.max_distance);
+        }
+    }
+
+    pub fn camera_mode(&self) -> CameraMode {
+        CameraMode::Orbit {
+            target: self.target,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            distance: self.distance,
+        }
+    }
+}
+
+/// WASD fly camera for level previews: mouse to look, WASD to move, Q/E for down/up and Shift to go faster.
+/// Call `update` every frame and draw with `camera_mode`.
+#[derive(Clone, Debug)]
+pub struct FlyController {
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// World units per second
+    pub speed: f32,
+    /// Radians turned per pixel the mouse moves
+    pub sensitivity: f32,
+    /// Only look around while this button is held. None always looks, e.g. with a grabbed cursor.
+    pub look_button: Option<MouseButton>,
+}
+
+impl Default for FlyController {
+    fn default() -> Self {
+        Self {
+            position: Vec3::new(0.0, 1.0, 5.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 5.0,
+            sensitivity: 0.003,
+            look_button: Some(MouseButton::Right),
+        }
+    }
+}
+
+impl FlyController {
+    pub fn new(position: Vec3) -> Self {
+        Self { position, ..default() }
+    }
+
+    /// Look and move by one frame of `dt` seconds
+    pub fn update(&mut self, input: &InputContext, dt: f32) {
+        if self.look_button.is_none_or(|button| input.mouse_down(button)) {
+            let delta = input.mouse_delta();
+            self.yaw -= delta.x * self.sensitivity;
+            self.pitch = (self.pitch - delta.y * self.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+        }
+
+        let rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+        let mut direction = Vec3::ZERO;
+        if input.key_down(KeyCode::KeyW) {
+            direction += rotation * Vec3::NEG_Z;
+        }
+        if input.key_down(KeyCode::KeyS) {
+            direction -= rotation * Vec3::NEG_Z;
+        }
+        if input.key_down(KeyCode::KeyD) {
+            direction += rotation * Vec3::X;
+        }
+        if input.key_down(KeyCode::KeyA) {
+            direction -= rotation * Vec3::X;
+        }
+        if input.key_down(KeyCode::KeyE) {
+            direction += Vec3::Y;
+        }
+        if input.key_down(KeyCode::KeyQ) {
+            direction -= Vec3::Y;
+        }
+
+        let speed = if input.key_down(KeyCode::ShiftLeft) { self.speed * 3.0 } else { self.speed };
+        self.position += direction.normalize_or_zero() * speed * dt;
+    }
+
+    pub fn camera_mode(&self) -> CameraMode {
+        CameraMode::FirstPerson {
+            position: self.position,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+}
+
+/// How a virtual resolution is fitted to the window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Scale as large as fits while keeping the aspect ratio, with black bars filling the rest
+    #[default]
+    Letterbox,
+    /// Fill the window, squashing or stretching if the aspect ratio differs
+    Stretch,
+    /// Like `Letterbox`, but only by whole multiples so pixel art stays crisp
+    IntegerScale,
+    /// Keep the aspect ratio and show more of the world on the longer side instead of bars
+    Expand,
+}
+
+/// A fixed design resolution that 2D layer cameras are scaled to fit in the window
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct VirtualResolution {
+    pub width: f32,
+    pub height: f32,
+    pub mode: ScaleMode,
+}
+
+impl VirtualResolution {
+    /// The size of the area scenes can see in virtual units, for a window of `window_size`
+    pub fn visible_size(&self, window_size: Vec2) -> Vec2 {
+        let size = Vec2::new(self.width, self.height);
+        if self.mode != ScaleMode::Expand || window_size.min_element() <= 0.0 {
+            return size;
+        }
+
+        let window_aspect = window_size.x / window_size.y;
+        if window_aspect > self.width / self.height {
+            Vec2::new(self.height * window_aspect, self.height)
+        } else {
+            Vec2::new(self.width, self.width / window_aspect)
+        }
+    }
+
+    /// The part of the window to draw into, in physical pixels, or None to use all of it
+    fn viewport(&self, window_size: UVec2) -> Option<Viewport> {
+        let fit = (window_size.as_vec2() / Vec2::new(self.width, self.height)).min_element();
+        let scale = match self.mode {
+            ScaleMode::Letterbox => fit,
+            ScaleMode::IntegerScale => fit.floor().max(1.0),
+            ScaleMode::Stretch | ScaleMode::Expand => return None,
+        };
+
+        let size = (Vec2::new(self.width, self.height) * scale).round().as_uvec2().min(window_size).max(UVec2::ONE);
+        Some(Viewport {
+            physical_position: (window_size - size) / 2,
+            physical_size: size,
+            ..default()
+        })
+    }
+
+    fn scaling_mode(&self) -> ScalingMode {
+        match self.mode {
+            ScaleMode::Expand => ScalingMode::AutoMin { min_width: self.width, min_height: self.height },
+            _ => ScalingMode::Fixed { width: self.width, height: self.height },
+        }
+    }
+}
+
+/// Part of the window a layer draws into, for split-screen or picture-in-picture.
+/// (0,0) is the top left. With a virtual resolution, it is part of the letterboxed area instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayerViewport {
+    /// Fractions of the window, e.g. `Rect::new(0.0, 0.0, 0.5, 1.0)` for the left half
+    Normalized(Rect),
+    /// Window pixels
+    Pixels(Rect),
+}
+
+impl LayerViewport {
+    /// The viewport in physical pixels, inside the area at `area_pos` of `area_size`
+    fn physical(&self, area_pos: UVec2, area_size: UVec2, scale_factor: f32) -> Viewport {
+        let (min, max) = match *self {
+            LayerViewport::Normalized(rect) => (rect.min * area_size.as_vec2(), rect.max * area_size.as_vec2()),
+            LayerViewport::Pixels(rect) => (rect.min * scale_factor, rect.max * scale_factor),
+        };
+
+        let min = min.round().max(Vec2::ZERO).as_uvec2().min(area_size - UVec2::ONE);
+        let max = max.round().as_uvec2().clamp(min + UVec2::ONE, area_size);
+        Viewport {
+            physical_position: area_pos + min,
+            physical_size: max - min,
+            ..default()
+        }
+    }
+}
+
+/// The viewports layers have asked for, kept until they are cleared
+#[derive(Resource, Default)]
+pub struct LayerViewports(pub HashMap<usize, LayerViewport>);
+
+/// The images 2D layers render into instead of the window, kept until they are cleared
+#[derive(Resource, Default)]
+pub struct LayerTargets(pub HashMap<usize, Handle<Image>>);
+
+/// The lenses of 3D layer cameras, kept until they are changed
+#[derive(Resource, Default)]
+pub struct LayerLenses(pub HashMap<usize, CameraLens>);
+
+/// The render settings of layer cameras, kept until they are changed
+#[derive(Resource, Default)]
+pub struct LayerRenderSettings(pub HashMap<usize, LayerSettings>);
+
+/// Everything layers have set on their cameras through `LayerContext`
+#[derive(SystemParam)]
+pub struct LayerCameraSettings<'w> {
+    viewports: Res<'w, LayerViewports>,
+    targets: Res<'w, LayerTargets>,
+    lenses: Res<'w, LayerLenses>,
+    settings: Res<'w, LayerRenderSettings>,
+}
+
+impl LayerCameraSettings<'_> {
+    fn lens(&self, layer: usize) -> CameraLens {
+        self.lenses.0.get(&layer).copied().unwrap_or_default()
+    }
+
+    fn settings(&self, layer: usize) -> LayerSettings {
+        self.settings.0.get(&layer).copied().unwrap_or_default()
+    }
+
+    fn own_viewport(&self, layer: usize) -> bool {
+        self.viewports.0.contains_key(&layer)
+    }
+}
+
+/// Clears the whole window behind letterboxed layer cameras, drawing the bars
+#[derive(Component)]
+pub(crate) struct LetterboxCamera;
+
+/// A layer camera rendering into an image rather than the window
+#[derive(Component)]
+pub(crate) struct TextureCamera;
+
+/// Smooth movement for a layer's 2D camera: following, bounds, zoom, rotation and screen shake.
+/// Set the layer to `CameraMode::Camera2d` as usual; the controller then moves that camera every frame.
+#[derive(Clone, Debug)]
+pub struct Camera2dController {
+    /// Where the camera looks, before shake
+    pub position: Vec2,
+    /// Zoom, as in `CameraMode::Camera2d` (2.0 shows twice as much of the world)
+    pub scale: f32,
+    /// Rotation in radians, counter-clockwise
+    pub rotation: f32,
+    /// The point the camera follows, if any
+    pub target: Option<Vec2>,
+    /// Roughly how many seconds the camera lags behind its target (0.0 keeps up exactly)
+    pub damping: f32,
+    /// Size of the box around the center that the target can move in without the camera following
+    pub dead_zone: Vec2,
+    /// Keep the view inside this world area. Smaller areas keep the view centered on them.
+    pub bounds: Option<Rect>,
+    /// Shake amount from 0.0 to 1.0. The shake grows with trauma squared, so small hits stay subtle.
+    pub trauma: f32,
+    /// Trauma lost per second
+    pub trauma_decay: f32,
+    /// Shake offset in world units at full trauma
+    pub max_shake_offset: f32,
+    /// Shake rotation in radians at full trauma
+    pub max_shake_angle: f32,
+    /// How quickly the shake jitters
+    pub shake_frequency: f32,
+
+    shake_time: f32,
+    shake_offset: Vec2,
+    shake_angle: f32,
+    view_rect: Rect,
+}
+
+impl Default for Camera2dController {
+    fn default() -> Self {
+        Self {
+            position: Vec2::ZERO,
+            scale: 1.0,
+            rotation: 0.0,
+            target: None,
+            damping: 0.1,
+            dead_zone: Vec2::ZERO,
+            bounds: None,
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            max_shake_offset: 20.0,
+            max_shake_angle: 0.1,
+            shake_frequency: 15.0,
+            shake_time: 0.0,
+            shake_offset: Vec2::ZERO,
+            shake_angle: 0.0,
+            view_rect: Rect::default(),
+        }
+    }
+}
+
+impl Camera2dController {
+    /// Follow a point, usually the player. Call every frame as it moves.
+    pub fn follow(&mut self, target: Vec2) -> &mut Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Stop following and stay where the camera is
+    pub fn stop_following(&mut self) -> &mut Self {
+        self.target = None;
+        self
+    }
+
+    /// Jump straight to a position, skipping the damping
+    pub fn snap_to(&mut self, position: Vec2) -> &mut Self {
+        self.position = position;
+        if self.target.is_some() {
+            self.target = Some(position);
+        }
+        self
+    }
+
+    /// Change the scale while keeping `point` (in world space) at the same place on screen,
+    /// e.g. to zoom towards the mouse
+    pub fn zoom_to(&mut self, point: Vec2, scale: f32) -> &mut Self {
+        let scale = scale.max(f32::EPSILON);
+        self.position = point + (self.position - point) * (scale / self.scale);
+        self.scale = scale;
+        self
+    }
+
+    /// Add trauma to shake the camera, e.g. 0.3 for a hit and 1.0 for an explosion
+    pub fn add_trauma(&mut self, amount: f32) -> &mut Self {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+        self
+    }
+
+    /// The world area seen last frame, including rotation and shake
+    pub fn view_rect(&self) -> Rect {
+        self.view_rect
+    }
+
+    /// Where the camera is this frame, including shake
+    pub fn final_position(&self) -> Vec2 {
+        self.position + self.shake_offset
+    }
+
+    /// The camera's rotation this frame, including shake
+    pub fn final_rotation(&self) -> f32 {
+        self.rotation + self.shake_angle
+    }
+
+    /// Advance by one frame for a view of `view_size` pixels
+    fn step(&mut self, dt: f32, view_size: Vec2) {
+        if let Some(target) = self.target {
+            // Only chase the part of the offset that leaves the dead zone
+            let offset = target - self.position;
+            let half_zone = self.dead_zone / 2.0;
+            let outside = offset - offset.clamp(-half_zone, half_zone);
+
+            let t = if self.damping > 0.0 { 1.0 - (-dt / self.damping).exp() } else { 1.0 };
+            self.position += outside * t;
+        }
+
+        let size = view_size * self.scale;
+        if let Some(bounds) = self.bounds {
+            self.position = clamp_axis(self.position, size, bounds);
+        }
+
+        self.trauma = (self.trauma - self.trauma_decay * dt).max(0.0);
+        self.shake_time += dt;
+        let shake = self.trauma * self.trauma;
+        let t = self.shake_time * self.shake_frequency;
+        self.shake_offset = Vec2::new(shake_noise(t, 0.0), shake_noise(t, 1.0)) * self.max_shake_offset * shake;
+        self.shake_angle = shake_noise(t, 2.0) * self.max_shake_angle * shake;
+
+        // The smallest axis-aligned box around the (possibly rotated) view
+        let (sin, cos) = self.final_rotation().sin_cos();
+        let half = size / 2.0;
+        let extent = Vec2::new(
+            cos.abs() * half.x + sin.abs() * half.y,
+            sin.abs() * half.x + cos.abs() * half.y,
+        );
+        self.view_rect = Rect::from_center_half_size(self.final_position(), extent);
+    }
+}
+
+/// Keep a view of `size` inside `bounds`, centering it on any axis where it doesn't fit
+fn clamp_axis(position: Vec2, size: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + size / 2.0;
+    let max = bounds.max - size / 2.0;
+    Vec2::new(
+        if min.x > max.x { bounds.center().x } else { position.x.clamp(min.x, max.x) },
+        if min.y > max.y { bounds.center().y } else { position.y.clamp(min.y, max.y) },
+    )
+}
+
+/// Smooth wobble from -1.0 to 1.0, different for each channel
+fn shake_noise(t: f32, channel: f32) -> f32 {
+    (t + channel * 12.9898).sin() * 0.6 + (t * 2.3 + channel * 78.233).sin() * 0.4
+}
+
+/// The 2D camera controllers of each layer
+#[derive(Resource, Default)]
+pub struct CameraControllers {
+    controllers: HashMap<usize, Camera2dController>,
+}
+
+impl CameraControllers {
+    /// The controller for a layer's 2D camera, created on first use
+    pub fn camera2d(&mut self, layer: usize) -> &mut Camera2dController {
+        self.controllers.entry(layer).or_default()
+    }
+
+    /// Stop controlling a layer's camera, leaving it to `set_camera` again
+    pub fn remove(&mut self, layer: usize) {
+        self.controllers.remove(&layer);
+    }
+
+    /// Move every controlled camera by one frame. Each layer is fitted to what its camera
+    /// showed at scale 1.0 last frame, or `default_size` before it has been drawn.
+    pub(crate) fn update(&mut self, dt: f32, views: &LayerViews, default_size: Vec2) {
+        for (layer, controller) in self.controllers.iter_mut() {
+            let view_size = views.get(*layer).and_then(LayerView::unscaled_size).unwrap_or(default_size);
+            controller.step(dt, view_size);
+        }
+    }
+}
+
+/// The parts of a layer camera needed to build its `LayerView`
+#[derive(QueryData)]
+pub struct LayerViewItem {
+    pub camera: &'static Camera,
+    pub transform: &'static GlobalTransform,
+    pub frustum: &'static Frustum,
+    pub layers: Option<&'static RenderLayers>,
+    pub projection: Option<&'static Projection>,
+    pub is_2d: Has<Camera2d>,
+    pub textured: Has<TextureCamera>,
+}
+
+/// What a layer's camera saw last frame, for culling and converting between world and screen positions.
+/// Screen positions are in window pixels with (0,0) at the top left.
+#[derive(Clone, Debug)]
+pub struct LayerView {
+    layer: usize,
+    camera: Camera,
+    transform: GlobalTransform,
+    frustum: Frustum,
+    /// The world size an orthographic camera shows at scale 1.0
+    unscaled_size: Option<Vec2>,
+    is_2d: bool,
+    textured: bool,
+}
+
+/// The layer a camera renders. Cameras without RenderLayers render layer 0,
+/// and cameras with empty RenderLayers (e.g. the letterbox camera) render none.
+pub(crate) fn layer_of(layers: Option<&RenderLayers>) -> Option<usize> {
+    match layers {
+        Some(layers) => layers.iter().next(),
+        None => Some(0),
+    }
+}
+
+impl LayerView {
+    /// Returns `None` for cameras that render no layers at all
+    pub(crate) fn new(item: LayerViewItemItem) -> Option<Self> {
+        let layer = layer_of(item.layers)?;
+        Some(Self {
+            layer,
+            camera: item.camera.clone(),
+            transform: *item.transform,
+            frustum: *item.frustum,
+            unscaled_size: match item.projection {
+                Some(Projection::Orthographic(ortho)) if ortho.scale > 0.0 => Some(ortho.area.size() / ortho.scale),
+                _ => None,
+            },
+            is_2d: item.is_2d,
+            textured: item.textured,
+        })
+    }
+
+    pub fn layer(&self) -> usize {
+        self.layer
+    }
+
+    /// Whether a window position is inside this camera's viewport
+    pub fn contains(&self, screen_pos: Vec2) -> bool {
+        self.camera.logical_viewport_rect().is_some_and(|rect| rect.contains(screen_pos))
+    }
+
+    /// Convert a window position to a point on the 2D plane this camera sees
+    pub fn screen_to_world(&self, screen_pos: Vec2) -> Option<Vec2> {
+        self.camera.viewport_to_world_2d(&self.transform, screen_pos).ok()
+    }
+
+    /// Convert a window position to a ray leaving the camera
+    pub fn screen_to_ray(&self, screen_pos: Vec2) -> Option<Ray3d> {
+        self.camera.viewport_to_world(&self.transform, screen_pos).ok()
+    }
+
+    /// Convert a world position to a window position, or None if the camera can't see it
+    pub fn world_to_screen(&self, world_pos: Vec3) -> Option<Vec2> {
+        self.camera.world_to_viewport(&self.transform, world_pos).ok()
+    }
+
+    /// The world area a 2D camera sees, including rotation. None for 3D cameras.
+    pub fn view_rect(&self) -> Option<Rect> {
+        if !self.is_2d {
+            return None;
+        }
+
+        let viewport = self.camera.logical_viewport_rect()?;
+        let corners = [viewport.min, viewport.max, vec2(viewport.min.x, viewport.max.y), vec2(viewport.max.x, viewport.min.y)];
+        corners.into_iter()
+            .map(|corner| self.screen_to_world(corner).map(|p| Rect::from_center_size(p, Vec2::ZERO)))
+            .reduce(|a, b| Some(a?.union(b?)))?
+    }
+
+    /// The world size an orthographic camera shows at scale 1.0, whatever its viewport,
+    /// render target or virtual resolution
+    pub(crate) fn unscaled_size(&self) -> Option<Vec2> {
+        self.unscaled_size
+    }
+
+    /// The volume a 3D camera sees
+    pub fn frustum(&self) -> &Frustum {
+        &self.frustum
+    }
+
+    /// Whether any of a world-space rectangle is inside a 2D camera's view.
+    /// Always true for 3D cameras.
+    pub fn is_rect_visible(&self, rect: Rect) -> bool {
+        self.view_rect().is_none_or(|view| !view.intersect(rect).is_empty())
+    }
+
+    /// Whether any of a sphere is inside the camera's frustum
+    pub fn is_sphere_visible(&self, center: Vec3, radius: f32) -> bool {
+        self.frustum.intersects_sphere(&Sphere { center: center.into(), radius }, true)
+    }
+}
+
+/// Every layer camera as it was last frame
+#[derive(Default)]
+pub struct LayerViews(pub(crate) Vec<LayerView>);
+
+impl LayerViews {
+    pub fn get(&self, layer: usize) -> Option<&LayerView> {
+        self.0.iter().find(|view| view.layer == layer)
+    }
+
+    /// A layer drawn to the window, leaving out layers that render into images
+    pub(crate) fn window_view(&self, layer: usize) -> Option<&LayerView> {
+        self.get(layer).filter(|view| !view.textured)
+    }
+
+    /// The world area a layer's 2D camera sees
+    pub fn view_rect(&self, layer: usize) -> Option<Rect> {
+        self.get(layer)?.view_rect()
+    }
+
+    /// The volume a layer's 3D camera sees
+    pub fn frustum(&self, layer: usize) -> Option<&Frustum> {
+        self.get(layer).map(LayerView::frustum)
+    }
+
+    /// Convert a world position to a window position as seen by a layer's camera.
+    /// None for layers rendering into images, which have no window position.
+    pub fn world_to_screen(&self, layer: usize, world_pos: Vec3) -> Option<Vec2> {
+        self.window_view(layer)?.world_to_screen(world_pos)
+    }
+
+    /// Convert a window position to a world position on a layer's 2D plane.
+    /// None for layers rendering into images, which have no window position.
+    pub fn screen_to_world(&self, layer: usize, screen_pos: Vec2) -> Option<Vec2> {
+        self.window_view(layer)?.screen_to_world(screen_pos)
+    }
+}
+
+/// Find where a ray hits an infinite plane, e.g. the ground under the mouse in a 3D scene.
+/// Returns None if the ray is parallel to the plane or points away from it.
+pub fn ray_plane_intersection(ray: Ray3d, plane_origin: Vec3, plane_normal: Vec3) -> Option<Vec3> {
+    let normal = Dir3::new(plane_normal).ok()?;
+    let distance = ray.intersect_plane(plane_origin, InfinitePlane3d { normal })?;
+    Some(ray.get_point(distance))
+}
+
 #[derive(QueryData)]
 #[query_data(mutable)]
 pub struct CameraItem {
     pub entity: Entity,
+    pub camera: &'static mut Camera,
+    pub render_target: &'static RenderTarget,
     pub layers: &'static RenderLayers,
     pub transform: Option<&'static mut Transform>,
     pub projection: Option<&'static mut Projection>,
     pub cam2d: Option<&'static Camera2d>,
-    pub cam3d: Option<&'static Camera3d>,
+    pub cam3d: Option<&'static mut Camera3d>,
+    pub msaa: Option<&'static Msaa>,
+    pub hdr: Has<Hdr>,
+    pub tonemapping: Option<&'static Tonemapping>,
 }
 
-pub fn manage_cameras(mut commands: Commands, mut queue: ResMut<CameraQueue>, mut query: Query<CameraItem, With<Camera>>) {
+/// Place every layer camera in the window: 2D projections are scaled to the virtual resolution,
+/// and layers share the letterboxed area unless they have a viewport of their own.
+pub fn apply_viewports(
+    mut commands: Commands,
+    resolution: Option<Res<VirtualResolution>>,
+    viewports: Res<LayerViewports>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_cameras: Query<(&mut Camera, &RenderLayers, Has<TextureCamera>), Without<LetterboxCamera>>,
+    mut q_projections: Query<&mut Projection, (With<Camera2d>, Without<TextureCamera>)>,
+    q_letterbox: Query<Entity, With<LetterboxCamera>>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    // Nothing to fit while the window is minimized
+    let window_size = window.physical_size();
+    if window_size.min_element() == 0 {
+        return;
+    }
+
+    let area = resolution.as_ref().and_then(|resolution| resolution.viewport(window_size));
+    let (area_pos, area_size) = area.as_ref().map_or((UVec2::ZERO, window_size), |v| (v.physical_position, v.physical_size));
+
+    // The bars are only needed while the layers leave part of the window uncovered
+    let uncovered = area.is_some() || !viewports.0.is_empty();
+    match (uncovered, q_letterbox.iter().next()) {
+        (true, None) => {
+            commands.spawn((
+                LetterboxCamera,
+                Camera2d,
+                Camera {
+                    order: -1,
+                    clear_color: ClearColorConfig::Custom(Color::BLACK),
+                    ..default()
+                },
+                RenderLayers::none(),
+            ));
+        }
+        (false, Some(entity)) => commands.entity(entity).despawn(),
+        _ => {}
+    }
+
+    // Only write when something changed, so Bevy doesn't recompute the cameras every frame.
+    // Layers rendering into images keep their own size.
+    for (mut camera, layers, _) in q_cameras.iter_mut().filter(|(_, _, textured)| !textured) {
+        let layer = layers.iter().next().unwrap_or(0);
+        let viewport = match viewports.0.get(&layer) {
+            Some(viewport) => Some(viewport.physical(area_pos, area_size, window.scale_factor())),
+            None => area.clone(),
+        };
+
+        let viewport_rect = |v: &Option<Viewport>| v.as_ref().map(|v| (v.physical_position, v.physical_size));
+        if viewport_rect(&camera.viewport) != viewport_rect(&viewport) {
+            camera.viewport = viewport;
+        }
+    }
+
+    let Some(resolution) = resolution else {
+        return;
+    };
+
+    let scaling_mode = resolution.scaling_mode();
+    for mut projection in q_projections.iter_mut() {
+        let needs_scaling = matches!(projection.as_ref(), Projection::Orthographic(ortho) if !same_scaling(ortho.scaling_mode, scaling_mode));
+        if needs_scaling && let Projection::Orthographic(ortho) = projection.as_mut() {
+            ortho.scaling_mode = scaling_mode;
+        }
+    }
+}
+
+fn same_scaling(a: ScalingMode, b: ScalingMode) -> bool {
+    match (a, b) {
+        (ScalingMode::Fixed { width: w1, height: h1 }, ScalingMode::Fixed { width: w2, height: h2 }) => w1 == w2 && h1 == h2,
+        (ScalingMode::AutoMin { min_width: w1, min_height: h1 }, ScalingMode::AutoMin { min_width: w2, min_height: h2 }) => w1 == w2 && h1 == h2,
+        (ScalingMode::FixedVertical { viewport_height: h1 }, ScalingMode::FixedVertical { viewport_height: h2 }) => h1 == h2,
+        _ => false,
+    }
+}
+
+/// Compare the parts of a projection that `CameraMode::view_3d` sets, ignoring the aspect ratio Bevy fills in
+fn same_projection(a: &Projection, b: &Projection) -> bool {
+    match (a, b) {
+        (Projection::Perspective(a), Projection::Perspective(b)) => a.fov == b.fov && a.near == b.near && a.far == b.far,
+        (Projection::Orthographic(a), Projection::Orthographic(b)) => {
+            same_scaling(a.scaling_mode, b.scaling_mode) && a.near == b.near && a.far == b.far && a.scale == b.scale
+        }
+        _ => false,
+    }
+}
+
+/// Number the layer cameras from 0 by their requested order, breaking ties by layer id, so no two cameras
+/// share an order and every window layer draws over the letterbox camera (order -1).
+/// Texture layers are numbered the same way, below every window layer.
+fn rank_orders(mut cameras: Vec<(bool, isize, usize)>) -> HashMap<usize, isize> {
+    cameras.sort_unstable();
+    let mut next = [0, -TEXTURE_ORDER_OFFSET];
+    cameras.into_iter().map(|(textured, _, layer)| {
+        let order = &mut next[textured as usize];
+        *order += 1;
+        (layer, *order - 1)
+    }).collect()
+}
+
+/// The camera settings for a layer. By default only the first window layer clears the screen,
+/// while texture layers and layers with their own viewport clear their own area.
+fn layer_camera(layer: usize, order: isize, textured: bool, cameras: &LayerCameraSettings) -> Camera {
+    let clear_color = match cameras.settings(layer).clear {
+        LayerClear::Auto if layer == 0 || textured || cameras.own_viewport(layer) => ClearColorConfig::Default,
+        LayerClear::Auto | LayerClear::DepthOnly | LayerClear::Nothing => ClearColorConfig::None,
+        LayerClear::Background => ClearColorConfig::Default,
+        LayerClear::Color(color) => ClearColorConfig::Custom(color),
+    };
+
+    Camera {
+        order,
+        clear_color,
+        ..default()
+    }
+}
+
+fn same_clear(a: ClearColorConfig, b: ClearColorConfig) -> bool {
+    match (a, b) {
+        (ClearColorConfig::Custom(a), ClearColorConfig::Custom(b)) => a == b,
+        (ClearColorConfig::Default, ClearColorConfig::Default) | (ClearColorConfig::None, ClearColorConfig::None) => true,
+        _ => false,
+    }
+}
+
+/// Keep a layer camera in step with its settings, writing only what changed
+fn apply_layer_settings(commands: &mut Commands, item: &mut CameraItemItem, layer: usize, order: isize, cameras: &LayerCameraSettings) {
+    let settings = cameras.settings(layer);
+    let base = layer_camera(layer, order, item.render_target.as_image().is_some(), cameras);
+    if item.camera.order != base.order {
+        item.camera.order = base.order;
+    }
+    if !same_clear(item.camera.clear_color, base.clear_color) {
+        item.camera.clear_color = base.clear_color;
+    }
+
+    if let Some(ref mut cam3d) = item.cam3d {
+        let keep_depth = settings.clear == LayerClear::Nothing;
+        if keep_depth != matches!(cam3d.depth_load_op, Camera3dDepthLoadOp::Load) {
+            cam3d.depth_load_op = if keep_depth { Camera3dDepthLoadOp::Load } else { Camera3dDepthLoadOp::default() };
+        }
+    }
+
+    // The camera may have been despawned by `CameraMode::None` this frame
+    let mut entity = commands.entity(item.entity);
+    if item.msaa != Some(&settings.msaa) {
+        entity.try_insert(settings.msaa);
+    }
+    if item.hdr != settings.hdr {
+        if settings.hdr {
+            entity.try_insert(Hdr);
+        } else {
+            entity.try_remove::<Hdr>();
+        }
+    }
+
+    // Going back to None restores the default Bevy gives each kind of camera
+    let tonemapping = settings.tonemapping.unwrap_or(if item.cam2d.is_some() { Tonemapping::None } else { Tonemapping::default() });
+    if item.tonemapping != Some(&tonemapping) {
+        entity.try_insert(tonemapping);
+    }
+}
+
+/// Point an existing layer camera at the window or at an image
+fn retarget_camera(commands: &mut Commands, item: &mut CameraItemItem, target: Option<&Handle<Image>>) {
+    if item.render_target.as_image() == target {
+        return;
+    }
+
+    // Order and clearing follow on the next frame, once the new target is in place.
+    // Viewports are in window pixels, so they don't carry over to an image
+    item.camera.viewport = None;
+
+    // The camera may have been despawned by `CameraMode::None` this frame
+    match target {
+        Some(image) => {
+            commands.entity(item.entity).try_insert((RenderTarget::from(image.clone()), TextureCamera));
+        }
+        None => {
+            commands.entity(item.entity).try_insert(RenderTarget::default()).try_remove::<TextureCamera>();
+        }
+    }
+}
+
+pub fn manage_cameras(mut commands: Commands, mut queue: ResMut<CameraQueue>, controllers: Res<CameraControllers>, cameras: LayerCameraSettings, mut query: Query<CameraItem, With<Camera>>) {
+    let mut despawned = Vec::new();
+    let mut spawns = Vec::new();
     for (layer, mode) in queue.0.drain(..) {
         let target_layer = RenderLayers::layer(layer);
         let mut found = false;
@@ -52,6 +1012,7 @@ pub fn manage_cameras(mut commands: Commands, mut queue: ResMut<CameraQueue>, mu
                 match mode {
                     CameraMode::None => {
                         commands.entity(item.entity).despawn();
+                        despawned.push(item.entity);
                     }
                     CameraMode::Camera2d { position, scale } => {
                         // Switch to 2D if needed
@@ -79,7 +1040,12 @@ pub fn manage_cameras(mut commands: Commands, mut queue: ResMut<CameraQueue>, mu
                             }
                         }
                     }
-                    CameraMode::Camera3d { position, target } => {
+                    CameraMode::Camera3d { .. } | CameraMode::Orbit { .. } | CameraMode::FirstPerson { .. } | CameraMode::Orthographic3d { .. } => {
+                        let rotation = item.transform.as_ref().map_or(Quat::IDENTITY, |t| t.rotation);
+                        let Some((transform, projection)) = mode.view_3d(cameras.lens(layer), rotation) else {
+                            continue;
+                        };
+
                         // Switch to 3D if needed
                         if item.cam3d.is_none() {
                             commands.entity(item.entity)
@@ -87,59 +1053,101 @@ pub fn manage_cameras(mut commands: Commands, mut queue: ResMut<CameraQueue>, mu
                                 .insert(Camera3d::default());
                         }
 
-                        // Update Position & Rotation safely
-                        if let Some(ref mut t) = item.transform {
-                            t.translation = position;
-
-                            // Only look_at if we aren't standing exactly on the target
-                            if position != target {
-                                t.look_at(target, Vec3::Y);
-                            }
+                        // Only write when something changed, as in `apply_viewports`
+                        if let Some(ref mut t) = item.transform
+                            && **t != transform {
+                            **t = transform;
                         }
-
-                        // Update Projection to Perspective
-                        if let Some(ref mut proj) = item.projection {
-                            if !matches!(**proj, Projection::Perspective(_)) {
-                                **proj = Projection::Perspective(PerspectiveProjection::default());
-                            }
+                        if let Some(ref mut proj) = item.projection
+                            && !same_projection(proj, &projection) {
+                            **proj = projection;
                         }
                     }
                 }
             }
         }
 
-        // Spawn new camera if not found
+        // Spawn new camera if not found, once every camera's order is known
         if !found && mode != CameraMode::None {
-            let camera_base = Camera {
-                order: layer as isize,
-                clear_color: if layer == 0 { ClearColorConfig::Default } else { ClearColorConfig::None },
-                ..default()
-            };
-
-            match mode {
-                CameraMode::Camera2d { position, scale } => {
-                    commands.spawn((
-                        Camera2d::default(),
-                        camera_base,
-                        target_layer,
-                        Projection::Orthographic(OrthographicProjection {
-                            scale,
-                            ..OrthographicProjection::default_2d()
-                        }),
-                        Transform::from_translation(position.extend(0.0)),
-                    ));
-                },
-                CameraMode::Camera3d { position, target } => {
+            spawns.push((layer, mode));
+        }
+    }
+
+    let existing = query.iter()
+        .filter(|item| !despawned.contains(&item.entity))
+        .filter_map(|item| Some((item.layers.iter().next()?, item.cam2d.is_some())));
+    let new = spawns.iter().map(|(layer, mode)| (*layer, matches!(mode, CameraMode::Camera2d { .. })));
+    let orders = rank_orders(existing.chain(new).map(|(layer, is_2d)| {
+        let textured = is_2d && cameras.targets.0.contains_key(&layer);
+        // Scene transitions cover everything, whatever order the other layers ask for
+        let order = if layer == TRANSITION_LAYER { isize::MAX } else { cameras.settings(layer).order.unwrap_or(layer as isize) };
+        (textured, order, layer)
+    }).collect());
+
+    for (layer, mode) in spawns {
+        let target_layer = RenderLayers::layer(layer);
+        let order = orders[&layer];
+        match mode {
+            CameraMode::Camera2d { position, scale } => {
+                let target = cameras.targets.0.get(&layer);
+                let mut camera = commands.spawn((
+                    Camera2d::default(),
+                    layer_camera(layer, order, target.is_some(), &cameras),
+                    target_layer,
+                    Projection::Orthographic(OrthographicProjection {
+                        scale,
+                        ..OrthographicProjection::default_2d()
+                    }),
+                    Transform::from_translation(position.extend(0.0)),
+                ));
+                if let Some(image) = target {
+                    camera.insert((RenderTarget::from(image.clone()), TextureCamera));
+                }
+            },
+            CameraMode::None => { }
+            CameraMode::Camera3d { .. } | CameraMode::Orbit { .. } | CameraMode::FirstPerson { .. } | CameraMode::Orthographic3d { .. } => {
+                if let Some((transform, projection)) = mode.view_3d(cameras.lens(layer), Quat::IDENTITY) {
                     commands.spawn((
                         Camera3d::default(),
-                        camera_base,
+                        layer_camera(layer, order, false, &cameras),
                         target_layer,
-                        Projection::Perspective(PerspectiveProjection::default()),
-                        Transform::from_translation(position).looking_at(target, Vec3::Y),
+                        projection,
+                        transform,
                     ));
-                },
-                CameraMode::None => { }
+                }
             }
         }
     }
-}
\ No newline at end of file
+
+    for mut item in query.iter_mut() {
+        // The letterbox camera has no layer
+        let Some(layer) = item.layers.iter().next() else {
+            continue;
+        };
+        let Some(&order) = orders.get(&layer) else {
+            continue;
+        };
+        apply_layer_settings(&mut commands, &mut item, layer, order, &cameras);
+
+        // Only 2D layers render into images
+        let target = cameras.targets.0.get(&layer).filter(|_| item.cam2d.is_some());
+        retarget_camera(&mut commands, &mut item, target);
+
+        // Controlled layers override the position, scale and rotation set above
+        if item.cam2d.is_none() {
+            continue;
+        }
+        let Some(controller) = controllers.controllers.get(&layer) else {
+            continue;
+        };
+
+        if let Some(ref mut t) = item.transform {
+            t.translation = controller.final_position().extend(0.0);
+            t.rotation = Quat::from_rotation_z(controller.final_rotation());
+        }
+        if let Some(ref mut proj) = item.projection
+            && let Projection::Orthographic(ref mut ortho) = **proj {
+            ortho.scale = controller.scale;
+        }
+    }
+}
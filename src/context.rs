@@ -1,8 +1,10 @@
+use bevy::image::ImageSampler;
 use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
 use std::cell::RefCell;
 
 use crate::core::audio::AudioContext;
-use crate::camera::{CameraMode, CameraQueue};
+use crate::camera::{CameraControllers, CameraLens, CameraMode, CameraQueue, LayerLenses, LayerRenderSettings, LayerSettings, LayerTargets, LayerView, LayerViewport, LayerViewports, LayerViews};
 use crate::core::fps::FpsResource;
 use crate::core::input::InputContext;
 use crate::core::system::SystemContext;
@@ -21,7 +23,11 @@ pub struct Context<'a> {
     pub asset_server: &'a AssetServer,
     pub audio: AudioContext<'a>,
     pub window: WindowContext<'a>,
+    pub cameras: &'a mut CameraControllers,
+    /// What each layer's camera saw last frame
+    pub views: &'a LayerViews,
     pub system: SystemContext,
+    pub(crate) fixed_delta: f32,
 }
 
 impl<'a> Context<'a> {
@@ -42,10 +48,24 @@ impl<'a> Context<'a> {
         self.asset_server.load(path.to_owned())
     }
 
+    /// Create a blank image that a 2D layer can render into with `LayerContext::set_render_target`.
+    /// It uses nearest sampling, so drawing it scaled up as a sprite keeps pixels sharp.
+    pub fn create_render_texture(&self, width: u32, height: u32) -> Handle<Image> {
+        let mut image = Image::new_target_texture(width, height, TextureFormat::bevy_default(), None);
+        image.sampler = ImageSampler::nearest();
+        self.asset_server.add(image)
+    }
+
     /// Get the current frames per second (FPS) value.
     pub fn fps(&self) -> f32 {
         self.fps.show_value
     }
+
+    /// Get the duration of one fixed update in seconds.
+    /// Returns 0.0 if no fixed timestep has been configured.
+    pub fn fixed_delta_secs(&self) -> f32 {
+        self.fixed_delta
+    }
 }
 
 pub struct LayerContext<'a> {
@@ -64,6 +84,12 @@ pub struct LayerContext<'a> {
     pub lights: LightContext<'a>,
 
     pub camera_queue: &'a mut CameraQueue,
+    pub viewports: &'a mut LayerViewports,
+    pub targets: &'a mut LayerTargets,
+    pub lenses: &'a mut LayerLenses,
+    pub settings: &'a mut LayerRenderSettings,
+    /// What this layer's camera saw last frame, if it has one
+    pub view: Option<&'a LayerView>,
 }
 
 impl<'a> LayerContext<'a> {
@@ -73,6 +99,51 @@ impl<'a> LayerContext<'a> {
         self.camera_queue.0.push((self.layer_id, mode));
     }
 
+    /// Skip 2D shapes and sprites that are entirely outside the camera's view.
+    /// The view is from last frame, so leave this off for layers whose camera jumps around.
+    pub fn set_culling(&mut self, enabled: bool) {
+        let view_rect = self.view.and_then(LayerView::view_rect).filter(|_| enabled);
+        self.draw2d.cull = view_rect;
+        self.sprites.cull = view_rect;
+    }
+
+    /// Draw this layer into part of the window only, or the whole window again with None.
+    /// The viewport stays until it is changed.
+    pub fn set_viewport(&mut self, viewport: Option<LayerViewport>) {
+        match viewport {
+            Some(viewport) => self.viewports.0.insert(self.layer_id, viewport),
+            None => self.viewports.0.remove(&self.layer_id),
+        };
+    }
+
+    /// Render this layer's 2D camera into an image (see `Context::create_render_texture`) instead of the window,
+    /// or into the window again with None. The target stays until it is changed.
+    pub fn set_render_target(&mut self, image: Option<Handle<Image>>) {
+        match image {
+            Some(image) => self.targets.0.insert(self.layer_id, image),
+            None => self.targets.0.remove(&self.layer_id),
+        };
+    }
+
+    /// Change the field of view and clipping distances of this layer's 3D camera, from the next `set_camera`.
+    /// The lens stays until it is changed.
+    pub fn set_lens(&mut self, lens: CameraLens) {
+        self.lenses.0.insert(self.layer_id, lens);
+    }
+
+    pub fn lens(&self) -> CameraLens {
+        self.lenses.0.get(&self.layer_id).copied().unwrap_or_default()
+    }
+
+    /// Change this layer's draw order, clearing, MSAA, HDR and tonemapping.
+    /// The settings stay until they are changed.
+    pub fn set_settings(&mut self, settings: LayerSettings) {
+        self.settings.0.insert(self.layer_id, settings);
+    }
+
+    pub fn settings(&self) -> LayerSettings {
+        self.settings.0.get(&self.layer_id).copied().unwrap_or_default()
+    }
 
     /// Draw the current FPS value at the specified position with the given color.
     pub fn draw_fps(&self, pos: Vec2, color: Color) {
@@ -87,7 +158,16 @@ pub struct DrawContext<'a> {
     pub fps: &'a FpsResource,
     pub asset_server: &'a AssetServer,
     pub camera_queue: &'a mut CameraQueue,
+    pub viewports: &'a mut LayerViewports,
+    pub targets: &'a mut LayerTargets,
+    pub lenses: &'a mut LayerLenses,
+    pub settings: &'a mut LayerRenderSettings,
+    /// What each layer's camera saw last frame
+    pub views: &'a LayerViews,
+    pub(crate) images: &'a Assets<Image>,
     pub clear_color: &'a mut ClearColor,
+    pub(crate) alpha: f32,
+    pub(crate) screen_size: Vec2,
 }
 
 impl <'a> DrawContext<'a> {
@@ -108,12 +188,14 @@ impl <'a> DrawContext<'a> {
             fps: self.fps,
 
             // 2. Initialize all sub-contexts with the SAME cell
-            draw2d: Geometry2d { queue: &queue_cell, layer_id: id },
+            draw2d: Geometry2d { queue: &queue_cell, layer_id: id, cull: None },
             draw3d: Geometry3d { queue: &queue_cell, layer_id: id },
 
             sprites: SpriteContext {
                 queue: &queue_cell,
-                layer_id: id
+                layer_id: id,
+                images: self.images,
+                cull: None,
             },
 
             text: TextContext {
@@ -127,6 +209,11 @@ impl <'a> DrawContext<'a> {
             },
 
             camera_queue: self.camera_queue,
+            viewports: self.viewports,
+            targets: self.targets,
+            lenses: self.lenses,
+            settings: self.settings,
+            view: self.views.get(id),
         };
 
         f(&mut ctx);
@@ -135,4 +222,15 @@ impl <'a> DrawContext<'a> {
     pub fn clear_background(&mut self, color: Color) {
         self.clear_color.0 = color;
     }
+
+    /// Get the size of the window in pixels.
+    pub fn screen_size(&self) -> Vec2 {
+        self.screen_size
+    }
+
+    /// Get how far the current frame sits between the previous and next fixed update (0.0 to 1.0).
+    /// Use it to blend between fixed states when drawing. Always 1.0 without a fixed timestep.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
 }
\ No newline at end of file
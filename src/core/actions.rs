@@ -0,0 +1,286 @@
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::io;
+use bevy::input::gamepad::{GamepadAxis, GamepadButton};
+use bevy::prelude::*;
+use bevy::reflect::{DynamicEnum, DynamicTuple, DynamicVariant, Typed, VariantInfo};
+
+/// A physical button that can trigger a named action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl From<KeyCode> for InputBinding {
+    fn from(key: KeyCode) -> Self {
+        InputBinding::Key(key)
+    }
+}
+
+impl From<MouseButton> for InputBinding {
+    fn from(button: MouseButton) -> Self {
+        InputBinding::Mouse(button)
+    }
+}
+
+impl From<GamepadButton> for InputBinding {
+    fn from(button: GamepadButton) -> Self {
+        InputBinding::Gamepad(button)
+    }
+}
+
+/// A source of values from -1.0 to 1.0 for a named axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AxisBinding {
+    /// Two keys acting as the negative and positive ends of the axis (e.g. A/D)
+    Keys { negative: KeyCode, positive: KeyCode },
+    /// An analog stick or trigger axis
+    Gamepad(GamepadAxis),
+}
+
+impl AxisBinding {
+    /// Create an axis from a pair of keys
+    pub fn keys(negative: KeyCode, positive: KeyCode) -> Self {
+        AxisBinding::Keys { negative, positive }
+    }
+}
+
+impl From<GamepadAxis> for AxisBinding {
+    fn from(axis: GamepadAxis) -> Self {
+        AxisBinding::Gamepad(axis)
+    }
+}
+
+/// Which state of a button is being asked about
+#[derive(Clone, Copy)]
+pub(crate) enum ButtonPhase {
+    Down,
+    Pressed,
+    Released,
+}
+
+impl ButtonPhase {
+    pub(crate) fn check<T: Copy + Eq + Hash + Send + Sync + 'static>(self, input: &ButtonInput<T>, button: T) -> bool {
+        match self {
+            ButtonPhase::Down => input.pressed(button),
+            ButtonPhase::Pressed => input.just_pressed(button),
+            ButtonPhase::Released => input.just_released(button),
+        }
+    }
+}
+
+/// Named actions and axes, and the inputs bound to them.
+/// Lives for the whole app, so bindings survive scene changes.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ActionMap {
+    actions: BTreeMap<String, Vec<InputBinding>>,
+    axes: BTreeMap<String, Vec<AxisBinding>>,
+}
+
+impl ActionMap {
+    /// Bind an action to a set of buttons, replacing any existing bindings.
+    pub fn bind_action(&mut self, name: &str, bindings: impl IntoIterator<Item = InputBinding>) {
+        self.actions.insert(name.to_string(), bindings.into_iter().collect());
+    }
+
+    /// Bind an axis to a set of sources, replacing any existing bindings.
+    pub fn bind_axis(&mut self, name: &str, bindings: impl IntoIterator<Item = AxisBinding>) {
+        self.axes.insert(name.to_string(), bindings.into_iter().collect());
+    }
+
+    /// Remove an action or axis and all its bindings.
+    pub fn unbind(&mut self, name: &str) {
+        self.actions.remove(name);
+        self.axes.remove(name);
+    }
+
+    /// Get the buttons bound to an action.
+    pub fn action_bindings(&self, name: &str) -> &[InputBinding] {
+        self.actions.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the sources bound to an axis.
+    pub fn axis_bindings(&self, name: &str) -> &[AxisBinding] {
+        self.axes.get(name).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Write the bindings in a simple line based format, e.g.
+    /// `action jump = key:Space, gamepad:South` or `axis move_x = keys:KeyA/KeyD, gamepad:LeftStickX`.
+    /// Numbered buttons and axes are written as e.g. `gamepad:Other(3)`.
+    /// Keys bound by their native code (`KeyCode::Unidentified`) have no name, so they are left out.
+    pub fn to_config_string(&self) -> String {
+        let mut out = String::new();
+
+        for (name, bindings) in &self.actions {
+            let list: Vec<String> = bindings.iter().filter_map(|b| match b {
+                InputBinding::Key(KeyCode::Unidentified(_)) => None,
+                InputBinding::Key(key) => Some(format!("key:{key:?}")),
+                InputBinding::Mouse(button) => Some(format!("mouse:{button:?}")),
+                InputBinding::Gamepad(button) => Some(format!("gamepad:{button:?}")),
+            }).collect();
+            out.push_str(&format!("action {} = {}\n", name, list.join(", ")));
+        }
+
+        for (name, bindings) in &self.axes {
+            let list: Vec<String> = bindings.iter().filter_map(|b| match b {
+                AxisBinding::Keys { negative: KeyCode::Unidentified(_), .. }
+                | AxisBinding::Keys { positive: KeyCode::Unidentified(_), .. } => None,
+                AxisBinding::Keys { negative, positive } => Some(format!("keys:{negative:?}/{positive:?}")),
+                AxisBinding::Gamepad(axis) => Some(format!("gamepad:{axis:?}")),
+            }).collect();
+            out.push_str(&format!("axis {} = {}\n", name, list.join(", ")));
+        }
+
+        out
+    }
+
+    /// Parse bindings written by `to_config_string`. Blank lines and lines starting with `#` are ignored.
+    pub fn from_config_str(text: &str) -> io::Result<Self> {
+        let mut map = ActionMap::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |what: &str| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}: {}", index + 1, what, line),
+            );
+
+            let (head, list) = line.split_once('=').ok_or_else(|| invalid("expected '='"))?;
+            let (kind, name) = head.trim().split_once(' ').ok_or_else(|| invalid("expected 'action' or 'axis' and a name"))?;
+            let name = name.trim();
+            let items = list.split(',').map(str::trim).filter(|s| !s.is_empty());
+
+            match kind {
+                "action" => {
+                    let bindings = items
+                        .map(|item| parse_input_binding(item).ok_or_else(|| invalid("unknown binding")))
+                        .collect::<io::Result<Vec<_>>>()?;
+                    map.actions.insert(name.to_string(), bindings);
+                }
+                "axis" => {
+                    let bindings = items
+                        .map(|item| parse_axis_binding(item).ok_or_else(|| invalid("unknown binding")))
+                        .collect::<io::Result<Vec<_>>>()?;
+                    map.axes.insert(name.to_string(), bindings);
+                }
+                _ => return Err(invalid("expected 'action' or 'axis'")),
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+fn parse_input_binding(item: &str) -> Option<InputBinding> {
+    let (kind, value) = item.split_once(':')?;
+    match kind {
+        "key" => parse_variant(value).map(InputBinding::Key),
+        "mouse" => parse_variant(value).map(InputBinding::Mouse),
+        "gamepad" => parse_variant(value).map(InputBinding::Gamepad),
+        _ => None,
+    }
+}
+
+fn parse_axis_binding(item: &str) -> Option<AxisBinding> {
+    let (kind, value) = item.split_once(':')?;
+    match kind {
+        "keys" => {
+            let (negative, positive) = value.split_once('/')?;
+            Some(AxisBinding::keys(parse_variant(negative)?, parse_variant(positive)?))
+        }
+        "gamepad" => parse_variant(value).map(AxisBinding::Gamepad),
+        _ => None,
+    }
+}
+
+/// Build an enum variant from its debug name using reflection. Handles unit variants
+/// (e.g. `KeyCode::Space`) and numbered ones (e.g. `GamepadButton::Other(3)`).
+pub(crate) fn parse_variant<T: FromReflect + Typed>(name: &str) -> Option<T> {
+    let name = name.trim();
+    let (variant, number) = match name.strip_suffix(')').and_then(|s| s.split_once('(')) {
+        Some((variant, number)) => (variant, Some(number.trim())),
+        None => (name, None),
+    };
+
+    // Check the variant exists first, `from_reflect` panics on unknown names
+    let info = T::type_info().as_enum().ok()?.variant(variant)?;
+    let fields = match (info, number) {
+        (VariantInfo::Unit(_), None) => DynamicVariant::Unit,
+        (VariantInfo::Tuple(tuple), Some(number)) if tuple.field_len() == 1 => {
+            let field = tuple.field_at(0)?;
+            let mut fields = DynamicTuple::default();
+            if field.is::<u8>() {
+                fields.insert(number.parse::<u8>().ok()?);
+            } else if field.is::<u16>() {
+                fields.insert(number.parse::<u16>().ok()?);
+            } else {
+                return None;
+            }
+            DynamicVariant::Tuple(fields)
+        }
+        _ => return None,
+    };
+
+    T::from_reflect(&DynamicEnum::new(variant, fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_round_trip_through_their_names() {
+        assert_eq!(parse_variant::<KeyCode>("Space"), Some(KeyCode::Space));
+        assert_eq!(parse_variant::<MouseButton>("Other(300)"), Some(MouseButton::Other(300)));
+        assert_eq!(parse_variant::<GamepadButton>("Other(7)"), Some(GamepadButton::Other(7)));
+        assert_eq!(parse_variant::<GamepadAxis>(" Other(2) "), Some(GamepadAxis::Other(2)));
+        assert_eq!(parse_variant::<KeyCode>("NotAKey"), None);
+        assert_eq!(parse_variant::<GamepadButton>("Other(x)"), None);
+        assert_eq!(parse_variant::<GamepadButton>("Other(300)"), None);
+        assert_eq!(parse_variant::<GamepadButton>("South(1)"), None);
+        assert_eq!(parse_variant::<KeyCode>("Unidentified(Xkb(38))"), None);
+    }
+
+    #[test]
+    fn config_round_trips() {
+        let mut map = ActionMap::default();
+        map.bind_action("jump", [KeyCode::Space.into(), GamepadButton::South.into(), GamepadButton::Other(9).into()]);
+        map.bind_action("fire", [MouseButton::Left.into(), MouseButton::Other(4).into()]);
+        map.bind_axis("move_x", [AxisBinding::keys(KeyCode::KeyA, KeyCode::KeyD), GamepadAxis::LeftStickX.into()]);
+        map.bind_axis("zoom", [GamepadAxis::Other(5).into()]);
+
+        let text = map.to_config_string();
+        let parsed = ActionMap::from_config_str(&text).unwrap();
+
+        assert_eq!(parsed.to_config_string(), text);
+        assert_eq!(parsed.action_bindings("jump"), map.action_bindings("jump"));
+        assert_eq!(parsed.action_bindings("fire"), map.action_bindings("fire"));
+        assert_eq!(parsed.axis_bindings("move_x"), map.axis_bindings("move_x"));
+        assert_eq!(parsed.axis_bindings("zoom"), map.axis_bindings("zoom"));
+    }
+
+    #[test]
+    fn config_skips_comments_and_reports_bad_lines() {
+        let parsed = ActionMap::from_config_str("# controls\n\naction jump = key:Space\n").unwrap();
+        assert_eq!(parsed.action_bindings("jump"), &[InputBinding::Key(KeyCode::Space)]);
+
+        let err = ActionMap::from_config_str("action jump = key:Space\naction fire = key:Nope\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2:"), "{err}");
+    }
+
+    #[test]
+    fn unidentified_keys_are_left_out() {
+        let mut map = ActionMap::default();
+        map.bind_action("odd", [KeyCode::Unidentified(bevy::input::keyboard::NativeKeyCode::Xkb(38)).into(), KeyCode::KeyQ.into()]);
+
+        let parsed = ActionMap::from_config_str(&map.to_config_string()).unwrap();
+        assert_eq!(parsed.action_bindings("odd"), &[InputBinding::Key(KeyCode::KeyQ)]);
+    }
+}
@@ -1,52 +1,475 @@
 use std::collections::HashMap;
+use std::time::Duration;
+use bevy::audio::{AudioSinkPlayback, PlaybackMode, Volume};
+use bevy::camera::visibility::RenderLayers;
 use bevy::prelude::*;
 
+use crate::camera::layer_of;
+use crate::core::synth::{SfxKey, SfxParams, Waveform};
+
+/// A mixer channel that sounds are routed through.
+/// Every bus is also scaled by `Master`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AudioBus {
+    Master,
+    Music,
+    Sfx,
+    Voice,
+    Custom(String),
+}
+
+/// Built-in names (`"master"`, `"music"`, `"sfx"`, `"voice"`) map to their own buses,
+/// any other name is a custom bus.
+impl From<&str> for AudioBus {
+    fn from(name: &str) -> Self {
+        match name {
+            "master" => AudioBus::Master,
+            "music" => AudioBus::Music,
+            "sfx" => AudioBus::Sfx,
+            "voice" => AudioBus::Voice,
+            _ => AudioBus::Custom(name.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BusSettings {
+    volume: f32,
+    muted: bool,
+}
+
+impl Default for BusSettings {
+    fn default() -> Self {
+        Self { volume: 1.0, muted: false }
+    }
+}
+
+impl BusSettings {
+    fn gain(&self) -> f32 {
+        if self.muted { 0.0 } else { self.volume }
+    }
+}
+
+/// Volume and mute state of every bus, plus music ducking.
+/// Lives for the whole app, so settings survive scene changes.
+#[derive(Resource, Clone, Debug)]
+pub struct AudioBuses {
+    buses: HashMap<AudioBus, BusSettings>,
+    /// Music volume multiplier while a voice clip plays (1.0 = no ducking)
+    duck_volume: f32,
+    /// Seconds to fade the music down or back up
+    duck_secs: f32,
+    /// Current ducking multiplier, fading towards `duck_volume` or 1.0
+    duck_level: f32,
+}
+
+impl Default for AudioBuses {
+    fn default() -> Self {
+        Self {
+            buses: HashMap::new(),
+            duck_volume: 1.0,
+            duck_secs: 0.25,
+            duck_level: 1.0,
+        }
+    }
+}
+
+impl AudioBuses {
+    fn settings(&self, bus: &AudioBus) -> BusSettings {
+        self.buses.get(bus).copied().unwrap_or_default()
+    }
+
+    /// The volume a bus actually plays at, after master volume, muting and ducking
+    fn gain(&self, bus: &AudioBus) -> f32 {
+        let master = self.settings(&AudioBus::Master).gain();
+        match bus {
+            AudioBus::Master => master,
+            AudioBus::Music => master * self.settings(bus).gain() * self.duck_level,
+            _ => master * self.settings(bus).gain(),
+        }
+    }
+}
+
+/// Tags a playing sound with its bus and the volume it was asked to play at
+#[derive(Component)]
+pub(crate) struct BusVolume {
+    bus: AudioBus,
+    volume: f32,
+    fade: Option<VolumeFade>,
+}
+
+impl BusVolume {
+    fn new(bus: AudioBus, volume: f32) -> Self {
+        Self { bus, volume, fade: None }
+    }
+
+    /// Start moving the volume to `to` over `secs`, replacing any fade in progress
+    fn fade_to(&mut self, to: f32, secs: f32, stop_when_done: bool) {
+        self.fade = Some(VolumeFade { from: self.volume, to, secs, elapsed: 0.0, stop_when_done });
+    }
+}
+
+/// A linear volume envelope, advanced each frame by `update_sounds`
+struct VolumeFade {
+    from: f32,
+    to: f32,
+    secs: f32,
+    elapsed: f32,
+    /// Despawn the sound once the fade finishes (used by fade outs)
+    stop_when_done: bool,
+}
+
+/// A handle to a one-shot sound, returned when it is played.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SoundId(u64);
+
+/// One-shot sounds that have been played and not yet finished
+#[derive(Resource, Default)]
+pub struct ActiveSounds {
+    next_id: u64,
+    /// `None` until `play_audio` has spawned the sound
+    sounds: HashMap<SoundId, Option<Entity>>,
+}
+
+impl ActiveSounds {
+    fn allocate(&mut self) -> SoundId {
+        let id = SoundId(self.next_id);
+        self.next_id += 1;
+        self.sounds.insert(id, None);
+        id
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.sounds.clear();
+    }
+}
+
+/// Keeps what is needed to play a one-shot sound again for its remaining repeats
+#[derive(Component)]
+pub(crate) struct OneShot {
+    id: SoundId,
+    source: Handle<AudioSource>,
+    settings: PlaybackSettings,
+    repeats_left: u32,
+}
+
+/// Something to stop: a looping sound's label or a one-shot sound's id
+#[derive(Clone, Debug, PartialEq)]
+pub enum SoundRef {
+    Label(String),
+    Id(SoundId),
+}
+
+impl From<&str> for SoundRef {
+    fn from(label: &str) -> Self {
+        SoundRef::Label(label.to_string())
+    }
+}
+
+impl From<SoundId> for SoundRef {
+    fn from(id: SoundId) -> Self {
+        SoundRef::Id(id)
+    }
+}
+
+/// How far to the side a fully panned sound is placed, matching the default listener's ear offset
+const PAN_DISTANCE: f32 = 2.0;
+
+/// The entity spatial sounds are heard from, and which camera it follows
+#[derive(Resource)]
+pub struct AudioListener {
+    entity: Entity,
+    /// Follow the camera on this layer, or the lowest 3D camera if `None`
+    layer: Option<usize>,
+}
+
+impl FromWorld for AudioListener {
+    fn from_world(world: &mut World) -> Self {
+        let entity = world.spawn((SpatialListener::default(), Transform::default())).id();
+        Self { entity, layer: None }
+    }
+}
+
 #[derive(Clone)]
 pub enum AudioCommand {
-    /// Play a sound once and auto-delete the entity when done.
+    /// Play a sound once (or `times` times in a row) and auto-delete the entity when done.
     PlayOnce {
+        id: SoundId,
         source: Handle<AudioSource>,
+        bus: AudioBus,
         volume: f32,
+        speed: f32,
+        /// -1.0 (left) to 1.0 (right)
+        pan: f32,
+        start: Duration,
+        times: u32,
+        /// Play in 3D space instead of panning
+        position: Option<Vec3>,
     },
     /// Start a looping sound and tag it with a string label so we can stop it later.
     PlayLoop {
         label: String,
         source: Handle<AudioSource>,
+        bus: AudioBus,
+        volume: f32,
+        position: Option<Vec3>,
+    },
+    /// Start a looping sound silently and fade it up to `volume` over `secs`.
+    FadeIn {
+        label: String,
+        source: Handle<AudioSource>,
+        bus: AudioBus,
+        volume: f32,
+        secs: f32,
+    },
+    /// Fade a looping sound to silence over `secs`, then stop it.
+    FadeOut {
+        label: String,
+        secs: f32,
+    },
+    /// Change a looping sound's volume over `secs` (0.0 for immediately).
+    SetVolume {
+        label: String,
         volume: f32,
+        secs: f32,
     },
+    /// Move a sound playing in 3D space.
+    SetPosition(SoundRef, Vec3),
     /// Pause a specific looping sound by its label.
     Pause(String),
     /// Resume a specific paused looping sound by its label.
     Resume(String),
     /// Stop a specific looping sound by its label.
     Stop(String),
+    /// Stop a one-shot sound.
+    StopSound(SoundId),
 }
 
 #[derive(Resource, Default)]
 pub struct AudioQueue(pub Vec<AudioCommand>);
 
+/// What is known about a looping sound's playback
+#[derive(Clone, Copy, Debug, Default)]
+struct LoopState {
+    /// Whether the loop should be paused, applied once its sink exists
+    paused: bool,
+    /// Playback position as of the last frame
+    position: Duration,
+}
+
+/// Looping sounds by label
+#[derive(Resource, Default)]
+pub struct ActiveLoops {
+    entities: HashMap<String, Entity>,
+    states: HashMap<String, LoopState>,
+}
+
+/// Synthesized sounds that are still in use, so playing the same effect again doesn't synthesize it again.
+/// Only ids are kept, so a sound is freed once nothing is playing it.
 #[derive(Resource, Default)]
-pub struct ActiveLoops(pub HashMap<String, Entity>);
+pub struct SfxCache {
+    sources: HashMap<SfxKey, AssetId<AudioSource>>,
+}
 
 pub struct AudioContext<'a> {
     pub(crate) queue: &'a mut AudioQueue,
     pub(crate) asset_server: &'a AssetServer,
+    pub(crate) sfx_cache: &'a mut SfxCache,
+    pub(crate) buses: &'a mut AudioBuses,
+    pub(crate) sounds: &'a mut ActiveSounds,
+    pub(crate) loops: &'a mut ActiveLoops,
+    pub(crate) listener: &'a mut AudioListener,
+}
+
+/// The slowest a one-shot sound can play (one hundredth of normal speed)
+const MIN_SPEED: f32 = 0.01;
+
+/// Options for playing a one-shot sound, created with `ctx.audio.sound(path)`.
+pub struct SoundBuilder<'c, 'a> {
+    audio: &'c mut AudioContext<'a>,
+    source: Handle<AudioSource>,
+    bus: AudioBus,
+    volume: f32,
+    speed: f32,
+    pan: f32,
+    start: Duration,
+    times: u32,
+    position: Option<Vec3>,
+}
+
+impl<'c, 'a> SoundBuilder<'c, 'a> {
+    /// Set the volume (0.0 to 1.0).
+    pub fn volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Route the sound through a bus (defaults to `Sfx`).
+    pub fn bus(mut self, bus: impl Into<AudioBus>) -> Self {
+        self.bus = bus.into();
+        self
+    }
+
+    /// Set the playback speed, where 2.0 is twice as fast and an octave higher.
+    /// Speeds below 0.01 are raised to it, so the sound always finishes.
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed.max(MIN_SPEED);
+        self
+    }
+
+    /// Shift the pitch by a factor (1.0 is unchanged).
+    /// The sound plays faster or slower to match, like a record player.
+    pub fn pitch(self, pitch: f32) -> Self {
+        self.speed(pitch)
+    }
+
+    /// Pan the sound from -1.0 (left) to 1.0 (right).
+    pub fn pan(mut self, pan: f32) -> Self {
+        self.pan = pan.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Start playing this many seconds into the sound.
+    pub fn start_at(mut self, secs: f32) -> Self {
+        self.start = Duration::from_secs_f32(secs.max(0.0));
+        self
+    }
+
+    /// Play the sound at a point in 3D space, so it gets quieter and pans with distance from the listener.
+    /// Overrides `pan`.
+    pub fn at(mut self, position: Vec3) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Play the sound this many times in a row (defaults to 1).
+    pub fn times(mut self, times: u32) -> Self {
+        self.times = times.max(1);
+        self
+    }
+
+    /// Queue the sound and return its id, for `stop` and `is_playing`.
+    pub fn play(self) -> SoundId {
+        let id = self.audio.sounds.allocate();
+        self.audio.queue.0.push(AudioCommand::PlayOnce {
+            id,
+            source: self.source,
+            bus: self.bus,
+            volume: self.volume,
+            speed: self.speed,
+            pan: self.pan,
+            start: self.start,
+            times: self.times,
+            position: self.position,
+        });
+        id
+    }
 }
 
 impl<'a> AudioContext<'a> {
     /// Play a sound once at 100% volume.
     /// Good for UI sounds, footsteps, explosions.
-    pub fn play(&mut self, path: &str) {
-        self.play_vol(path, 1.0);
+    pub fn play(&mut self, path: &str) -> SoundId {
+        self.play_vol(path, 1.0)
     }
 
-    /// Play a sound once with specific volume (0.0 to 1.0).
-    pub fn play_vol(&mut self, path: &str, volume: f32) {
-        let handle = self.asset_server.load(path.to_owned());
-        self.queue.0.push(AudioCommand::PlayOnce {
-            source: handle,
-            volume,
+    /// Play a sound once with specific volume (0.0 to 1.0) on the `Sfx` bus.
+    pub fn play_vol(&mut self, path: &str, volume: f32) -> SoundId {
+        self.play_on(AudioBus::Sfx, path, volume)
+    }
+
+    /// Play a sound once through a specific bus, e.g. `AudioBus::Voice` for dialogue.
+    pub fn play_on(&mut self, bus: impl Into<AudioBus>, path: &str, volume: f32) -> SoundId {
+        self.sound(path).bus(bus).volume(volume).play()
+    }
+
+    /// Set up a one-shot sound with pitch, panning and other options, e.g.
+    /// `ctx.audio.sound("hit.ogg").pitch(1.2).pan(-0.5).play()`
+    pub fn sound(&mut self, path: &str) -> SoundBuilder<'_, 'a> {
+        let source = self.asset_server.load(path.to_owned());
+        self.sound_source(source)
+    }
+
+    /// Set up a one-shot sound from a synthesized effect, e.g.
+    /// `ctx.audio.sfx(&SfxParams::pickup(3)).volume(0.5).play()`
+    pub fn sfx(&mut self, params: &SfxParams) -> SoundBuilder<'_, 'a> {
+        let key = params.key();
+        let cached = self.sfx_cache.sources.get(&key).and_then(|&id| self.asset_server.get_id_handle(id));
+
+        let source = cached.unwrap_or_else(|| {
+            // Forget sounds that have been freed, so the cache doesn't grow with every new effect
+            let asset_server = self.asset_server;
+            self.sfx_cache.sources.retain(|_, id| asset_server.get_id_handle(*id).is_some());
+
+            let source = asset_server.add(params.to_audio_source());
+            self.sfx_cache.sources.insert(key, source.id());
+            source
         });
+        self.sound_source(source)
+    }
+
+    /// Play a synthesized sound effect once. No asset files needed.
+    pub fn play_sfx(&mut self, params: &SfxParams) -> SoundId {
+        self.sfx(params).play()
+    }
+
+    /// Play a plain tone once, e.g. `play_tone(440.0, 0.25, Waveform::Square)` for a beep.
+    pub fn play_tone(&mut self, freq: f32, secs: f32, waveform: Waveform) -> SoundId {
+        self.play_sfx(&SfxParams::tone(freq, secs, waveform))
+    }
+
+    fn sound_source(&mut self, source: Handle<AudioSource>) -> SoundBuilder<'_, 'a> {
+        SoundBuilder {
+            audio: self,
+            source,
+            bus: AudioBus::Sfx,
+            volume: 1.0,
+            speed: 1.0,
+            pan: 0.0,
+            start: Duration::ZERO,
+            times: 1,
+            position: None,
+        }
+    }
+
+    /// Play a sound once at a point in 3D space.
+    pub fn play_at(&mut self, path: &str, position: Vec3) -> SoundId {
+        self.sound(path).at(position).play()
+    }
+
+    /// Returns true while a one-shot sound is queued or playing,
+    /// or while a looping sound is started and not paused.
+    pub fn is_playing(&self, sound: impl Into<SoundRef>) -> bool {
+        match sound.into() {
+            SoundRef::Label(label) => self.loops.states.get(&label).is_some_and(|state| !state.paused),
+            SoundRef::Id(id) => self.sounds.sounds.contains_key(&id),
+        }
+    }
+
+    /// Returns true if a looping sound is paused.
+    pub fn is_paused(&self, label: &str) -> bool {
+        self.loops.states.get(label).is_some_and(|state| state.paused)
+    }
+
+    /// How far into its track a looping sound is, in seconds.
+    /// Returns None if there is no loop with this label.
+    pub fn position(&self, label: &str) -> Option<f32> {
+        self.loops.states.get(label).map(|state| state.position.as_secs_f32())
+    }
+
+    /// The labels of all looping sounds that are playing (not paused), in alphabetical order.
+    pub fn playing_labels(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = self.loops.states.iter()
+            .filter(|(_, state)| !state.paused)
+            .map(|(label, _)| label.as_str())
+            .collect();
+        labels.sort_unstable();
+        labels
+    }
+
+    /// Note a newly started loop so queries see it before it has spawned
+    fn start_loop(&mut self, label: &str) {
+        self.loops.states.entry(label.to_string()).or_default();
     }
 
     /// Start a looping sound (like music or engine hum).
@@ -55,90 +478,398 @@ impl<'a> AudioContext<'a> {
         self.play_loop_vol(label, path, 1.0);
     }
 
-    /// Start a looping sound with specific volume.
+    /// Start a looping sound with specific volume on the `Music` bus.
     pub fn play_loop_vol(&mut self, label: &str, path: &str, volume: f32) {
+        self.play_loop_on(AudioBus::Music, label, path, volume);
+    }
+
+    /// Start a looping sound through a specific bus, e.g. `AudioBus::Sfx` for an engine hum.
+    pub fn play_loop_on(&mut self, bus: impl Into<AudioBus>, label: &str, path: &str, volume: f32) {
+        self.start_loop(label);
         let handle = self.asset_server.load(path.to_owned());
         self.queue.0.push(AudioCommand::PlayLoop {
             label: label.to_string(),
             source: handle,
+            bus: bus.into(),
             volume,
+            position: None,
+        });
+    }
+
+    /// Start a looping sound at a point in 3D space on the `Sfx` bus, e.g. a humming generator.
+    /// Move it with `set_position`.
+    pub fn play_loop_at(&mut self, label: &str, path: &str, position: Vec3) {
+        self.start_loop(label);
+        let handle = self.asset_server.load(path.to_owned());
+        self.queue.0.push(AudioCommand::PlayLoop {
+            label: label.to_string(),
+            source: handle,
+            bus: AudioBus::Sfx,
+            volume: 1.0,
+            position: Some(position),
+        });
+    }
+
+    /// Move a sound that was played at a position, by its label or id.
+    pub fn set_position(&mut self, sound: impl Into<SoundRef>, position: Vec3) {
+        self.queue.0.push(AudioCommand::SetPosition(sound.into(), position));
+    }
+
+    /// Hear spatial sounds from the camera on this layer.
+    /// `None` (the default) uses the 3D camera on the lowest layer.
+    pub fn set_listener_layer(&mut self, layer: Option<usize>) {
+        self.listener.layer = layer;
+    }
+
+    /// Start a looping sound on the `Music` bus from silence, fading up to full volume over `secs`.
+    pub fn fade_in(&mut self, label: &str, path: &str, secs: f32) {
+        self.start_loop(label);
+        let handle = self.asset_server.load(path.to_owned());
+        self.queue.0.push(AudioCommand::FadeIn {
+            label: label.to_string(),
+            source: handle,
+            bus: AudioBus::Music,
+            volume: 1.0,
+            secs,
+        });
+    }
+
+    /// Fade a looping sound out over `secs`, then stop it.
+    /// The label is free to reuse straight away.
+    pub fn fade_out(&mut self, label: &str, secs: f32) {
+        self.loops.states.remove(label);
+        self.queue.0.push(AudioCommand::FadeOut {
+            label: label.to_string(),
+            secs,
         });
     }
 
-    /// Stop a specific looping sound by the label you gave it.
-    pub fn stop(&mut self, label: &str) {
-        self.queue.0.push(AudioCommand::Stop(label.to_string()));
+    /// Fade out the loop `from_label` while fading in `path` as `to_label`.
+    /// Handy for switching music between scenes.
+    pub fn crossfade(&mut self, from_label: &str, to_label: &str, path: &str, secs: f32) {
+        self.fade_out(from_label, secs);
+        self.fade_in(to_label, path, secs);
+    }
+
+    /// Change a looping sound's volume (0.0 to 1.0), gliding there over `over_secs`.
+    pub fn set_volume(&mut self, label: &str, volume: f32, over_secs: f32) {
+        self.queue.0.push(AudioCommand::SetVolume {
+            label: label.to_string(),
+            volume,
+            secs: over_secs,
+        });
+    }
+
+    /// Stop a looping sound by the label you gave it, or a one-shot sound by its id.
+    pub fn stop(&mut self, sound: impl Into<SoundRef>) {
+        self.queue.0.push(match sound.into() {
+            SoundRef::Label(label) => {
+                self.loops.states.remove(&label);
+                AudioCommand::Stop(label)
+            }
+            SoundRef::Id(id) => AudioCommand::StopSound(id),
+        });
     }
 
     /// Pause a looping sound without destroying it.
+    /// Works on a loop that is still loading; it starts paused.
     pub fn pause(&mut self, label: &str) {
+        if let Some(state) = self.loops.states.get_mut(label) {
+            state.paused = true;
+        }
         self.queue.0.push(AudioCommand::Pause(label.to_string()));
     }
 
     /// Resume a paused sound.
     pub fn resume(&mut self, label: &str) {
+        if let Some(state) = self.loops.states.get_mut(label) {
+            state.paused = false;
+        }
         self.queue.0.push(AudioCommand::Resume(label.to_string()));
     }
+
+    /// Set a bus volume (0.0 to 1.0). Applies to sounds that are already playing.
+    pub fn set_bus_volume(&mut self, bus: impl Into<AudioBus>, volume: f32) {
+        self.buses.buses.entry(bus.into()).or_default().volume = volume.max(0.0);
+    }
+
+    /// Get a bus volume, ignoring mute and ducking.
+    pub fn bus_volume(&self, bus: impl Into<AudioBus>) -> f32 {
+        self.buses.settings(&bus.into()).volume
+    }
+
+    /// Mute or unmute a bus without losing its volume.
+    pub fn set_bus_muted(&mut self, bus: impl Into<AudioBus>, muted: bool) {
+        self.buses.buses.entry(bus.into()).or_default().muted = muted;
+    }
+
+    /// Returns true if a bus is muted.
+    pub fn is_bus_muted(&self, bus: impl Into<AudioBus>) -> bool {
+        self.buses.settings(&bus.into()).muted
+    }
+
+    /// Lower the `Music` bus to `volume` (0.0 to 1.0) while anything on the `Voice` bus is playing,
+    /// fading over `secs`. Use a volume of 1.0 to turn ducking off.
+    pub fn set_music_ducking(&mut self, volume: f32, secs: f32) {
+        self.buses.duck_volume = volume.clamp(0.0, 1.0);
+        self.buses.duck_secs = secs.max(0.0);
+    }
 }
 
-pub fn play_audio(mut commands: Commands, mut queue: ResMut<AudioQueue>, mut active_loops: ResMut<ActiveLoops>, sinks: Query<&mut AudioSink>,) {
+/// A sound's sink, whether it plays in stereo or in 3D space
+fn playback<'s>((sink, spatial): (Option<&'s AudioSink>, Option<&'s SpatialAudioSink>)) -> Option<&'s dyn AudioSinkPlayback> {
+    match (sink, spatial) {
+        (Some(sink), _) => Some(sink),
+        (None, Some(sink)) => Some(sink),
+        (None, None) => None,
+    }
+}
+
+pub fn play_audio(mut commands: Commands, mut queue: ResMut<AudioQueue>, mut active_loops: ResMut<ActiveLoops>, mut sounds: ResMut<ActiveSounds>, buses: Res<AudioBuses>, listener: Res<AudioListener>,) {
     // We drain the queue so it is empty for the next frame
     for cmd in queue.0.drain(..) {
         match cmd {
-            AudioCommand::PlayOnce { source, volume } => {
-                commands.spawn((
-                    AudioPlayer(source),
-                    PlaybackSettings {
-                        mode: bevy::audio::PlaybackMode::Despawn, // Important: Auto-cleanup
-                        volume: bevy::audio::Volume::Linear(volume),
-                        ..default()
+            AudioCommand::PlayOnce { id, source, bus, volume, speed, pan, start, times, position } => {
+                // Stopped before it got here
+                if !sounds.sounds.contains_key(&id) {
+                    continue;
+                }
+
+                let settings = PlaybackSettings {
+                    // Only remove the player when done, so `update_sounds` can repeat or despawn it
+                    mode: PlaybackMode::Remove,
+                    volume: Volume::Linear(volume * buses.gain(&bus)),
+                    speed,
+                    spatial: position.is_some() || pan != 0.0,
+                    start_position: (!start.is_zero()).then_some(start),
+                    ..default()
+                };
+
+                let mut entity = commands.spawn((
+                    AudioPlayer(source.clone()),
+                    settings,
+                    BusVolume::new(bus, volume),
+                    OneShot {
+                        id,
+                        source,
+                        settings: PlaybackSettings { start_position: None, ..settings },
+                        repeats_left: times.saturating_sub(1),
                     },
                 ));
+
+                match position {
+                    Some(position) => {
+                        entity.insert(Transform::from_translation(position));
+                    }
+                    None => {
+                        // Panning places the sound beside the listener's ears, moving with it
+                        entity.insert((Transform::from_xyz(pan * PAN_DISTANCE, 0.0, 0.0), ChildOf(listener.entity)));
+                    }
+                }
+                let entity = entity.id();
+
+                sounds.sounds.insert(id, Some(entity));
             }
 
-            AudioCommand::PlayLoop { label, source, volume } => {
+            AudioCommand::PlayLoop { label, source, bus, volume, position } => {
                 // strict: if a loop with this name already exists, ignore this request
-                if active_loops.0.contains_key(&label) {
+                if active_loops.entities.contains_key(&label) {
                     continue;
                 }
 
+                let state = *active_loops.states.entry(label.clone()).or_default();
                 let entity = commands.spawn((
                     AudioPlayer(source),
                     PlaybackSettings {
-                        mode: bevy::audio::PlaybackMode::Loop, // Loop forever
-                        volume: bevy::audio::Volume::Linear(volume),
+                        mode: PlaybackMode::Loop, // Loop forever
+                        volume: Volume::Linear(volume * buses.gain(&bus)),
+                        spatial: position.is_some(),
+                        paused: state.paused,
                         ..default()
                     },
+                    BusVolume::new(bus, volume),
+                    Transform::from_translation(position.unwrap_or_default()),
                 )).id();
 
                 // Save the ID so we can find it later
-                active_loops.0.insert(label, entity);
+                active_loops.entities.insert(label, entity);
             }
 
-            AudioCommand::Pause(label) => {
-                if let Some(&entity) = active_loops.0.get(&label) {
-                    // Try to get the sink. It might not exist yet if audio is loading.
-                    if let Ok(sink) = sinks.get(entity) {
-                        sink.pause();
-                    }
+            AudioCommand::FadeIn { label, source, bus, volume, secs } => {
+                if active_loops.entities.contains_key(&label) {
+                    continue;
                 }
+
+                let mut tag = BusVolume::new(bus, 0.0);
+                tag.fade_to(volume, secs, false);
+
+                let state = *active_loops.states.entry(label.clone()).or_default();
+                let entity = commands.spawn((
+                    AudioPlayer(source),
+                    PlaybackSettings {
+                        mode: PlaybackMode::Loop,
+                        volume: Volume::SILENT,
+                        paused: state.paused,
+                        ..default()
+                    },
+                    tag,
+                )).id();
+
+                active_loops.entities.insert(label, entity);
             }
 
-            AudioCommand::Resume(label) => {
-                if let Some(&entity) = active_loops.0.get(&label) {
-                    if let Ok(sink) = sinks.get(entity) {
-                        sink.play();
-                    }
+            AudioCommand::FadeOut { label, secs } => {
+                // Forget the label now so a new loop can take it while this one fades
+                active_loops.states.remove(&label);
+                if let Some(entity) = active_loops.entities.remove(&label) {
+                    // Queued so it also works on a loop spawned earlier this frame
+                    commands.entity(entity).entry::<BusVolume>().and_modify(move |mut tag| {
+                        tag.fade_to(0.0, secs, true);
+                    });
+                }
+            }
+
+            AudioCommand::SetVolume { label, volume, secs } => {
+                if let Some(&entity) = active_loops.entities.get(&label) {
+                    commands.entity(entity).entry::<BusVolume>().and_modify(move |mut tag| {
+                        tag.fade_to(volume, secs, false);
+                    });
+                }
+            }
+
+            AudioCommand::SetPosition(sound, position) => {
+                let entity = match sound {
+                    SoundRef::Label(label) => active_loops.entities.get(&label).copied(),
+                    SoundRef::Id(id) => sounds.sounds.get(&id).copied().flatten(),
+                };
+                if let Some(entity) = entity {
+                    commands.entity(entity).try_insert(Transform::from_translation(position));
                 }
             }
 
+            // The sink might not exist yet if audio is loading, so `sync_loops` applies these
+            // to the sink, and the settings it will be created with are updated here
+            AudioCommand::Pause(label) => {
+                set_loop_paused(&mut commands, &mut active_loops, &label, true);
+            }
+
+            AudioCommand::Resume(label) => {
+                set_loop_paused(&mut commands, &mut active_loops, &label, false);
+            }
+
             AudioCommand::Stop(label) => {
+                active_loops.states.remove(&label);
                 // Look up the entity ID
-                if let Some(entity) = active_loops.0.remove(&label) {
+                if let Some(entity) = active_loops.entities.remove(&label) {
                     // Kill the sound entity
                     commands.entity(entity).despawn();
                 }
             }
+
+            AudioCommand::StopSound(id) => {
+                if let Some(Some(entity)) = sounds.sounds.remove(&id) {
+                    commands.entity(entity).despawn();
+                }
+            }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Note whether a loop should be paused, and start its sink that way if it hasn't been created yet
+fn set_loop_paused(commands: &mut Commands, loops: &mut ActiveLoops, label: &str, paused: bool) {
+    if let Some(state) = loops.states.get_mut(label) {
+        state.paused = paused;
+    }
+    if let Some(&entity) = loops.entities.get(label) {
+        commands.entity(entity).entry::<PlaybackSettings>().and_modify(move |mut settings| {
+            settings.paused = paused;
+        });
+    }
+}
+
+/// Advance volume fades, and repeat or clean up one-shot sounds once they finish.
+pub fn update_sounds(mut commands: Commands, time: Res<Time>, mut sounds: ResMut<ActiveSounds>, mut volumes: Query<(Entity, &mut BusVolume)>, mut finished: Query<(Entity, &mut OneShot), Without<AudioPlayer>>,) {
+    // Advance volume envelopes
+    for (entity, mut tag) in &mut volumes {
+        let Some(fade) = tag.fade.as_mut() else {
+            continue;
+        };
+
+        fade.elapsed += time.delta_secs();
+        let t = if fade.secs > 0.0 { (fade.elapsed / fade.secs).min(1.0) } else { 1.0 };
+        let (volume, done, stop) = (fade.from + (fade.to - fade.from) * t, t >= 1.0, fade.stop_when_done);
+
+        tag.volume = volume;
+        if done {
+            tag.fade = None;
+            if stop {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    // Bevy removes the player from a one-shot when it reaches the end
+    for (entity, mut shot) in &mut finished {
+        if shot.repeats_left > 0 {
+            shot.repeats_left -= 1;
+            commands.entity(entity).insert((AudioPlayer(shot.source.clone()), shot.settings));
+        } else {
+            sounds.sounds.remove(&shot.id);
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Pause or resume each loop's sink to match what was asked for, and note its playback position.
+pub fn sync_loops(mut loops: ResMut<ActiveLoops>, sinks: Query<(Option<&AudioSink>, Option<&SpatialAudioSink>)>) {
+    let ActiveLoops { entities, states } = &mut *loops;
+
+    for (label, entity) in entities.iter() {
+        let (Some(state), Some(sink)) = (states.get_mut(label), sinks.get(*entity).ok().and_then(playback)) else {
+            continue;
+        };
+
+        if state.paused != sink.is_paused() {
+            if state.paused {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+        state.position = sink.position();
+    }
+}
+
+/// Move the listener to the chosen camera, so spatial sounds are heard from where the player looks.
+/// Cameras that render no layers (like the letterbox camera) are never chosen.
+pub fn update_listener(listener: Res<AudioListener>, cameras: Query<(&Camera, &GlobalTransform, Option<&RenderLayers>, Has<Camera3d>)>, mut transforms: Query<&mut Transform, With<SpatialListener>>,) {
+    let camera = match listener.layer {
+        Some(layer) => cameras.iter().find(|(_, _, layers, _)| layer_of(*layers) == Some(layer)),
+        None => cameras.iter()
+            .filter(|(_, _, layers, is_3d)| *is_3d && layer_of(*layers).is_some())
+            .min_by_key(|(_, _, layers, _)| layer_of(*layers)),
+    };
+
+    if let (Some((_, camera_transform, _, _)), Ok(mut transform)) = (camera, transforms.get_mut(listener.entity)) {
+        *transform = camera_transform.compute_transform();
+    }
+}
+
+/// Keep every playing sound in step with its bus volume, and duck the music while voices play.
+pub fn apply_bus_volumes(time: Res<Time>, mut buses: ResMut<AudioBuses>, mut sinks: Query<(&mut AudioSink, &BusVolume)>, mut spatial_sinks: Query<(&mut SpatialAudioSink, &BusVolume)>,) {
+    let voice_playing = sinks.iter()
+        .any(|(sink, tag)| tag.bus == AudioBus::Voice && !sink.is_paused() && !sink.empty())
+        || spatial_sinks.iter()
+        .any(|(sink, tag)| tag.bus == AudioBus::Voice && !sink.is_paused() && !sink.empty());
+
+    let target = if voice_playing { buses.duck_volume } else { 1.0 };
+    let step = if buses.duck_secs > 0.0 { time.delta_secs() / buses.duck_secs } else { 1.0 };
+    buses.duck_level += (target - buses.duck_level).clamp(-step, step);
+
+    for (mut sink, tag) in &mut sinks {
+        sink.set_volume(Volume::Linear(tag.volume * buses.gain(&tag.bus)));
+    }
+    for (mut sink, tag) in &mut spatial_sinks {
+        sink.set_volume(Volume::Linear(tag.volume * buses.gain(&tag.bus)));
+    }
+}
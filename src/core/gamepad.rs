@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+/// Identifies a connected gamepad. Stays the same if the pad disconnects and reconnects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct GamepadId(pub(crate) Entity);
+
+/// Dead zones applied on top of the values reported by the gamepad
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GamepadConfig {
+    /// Sticks report zero until pushed further than this (0.0 to 1.0)
+    pub stick_deadzone: f32,
+    /// Triggers report zero until pulled further than this (0.0 to 1.0)
+    pub trigger_deadzone: f32,
+}
+
+impl Default for GamepadConfig {
+    fn default() -> Self {
+        Self {
+            stick_deadzone: 0.15,
+            trigger_deadzone: 0.05,
+        }
+    }
+}
+
+/// A gamepad as seen by the InputContext for one frame
+pub(crate) struct ConnectedGamepad<'a> {
+    pub id: GamepadId,
+    pub pad: &'a Gamepad,
+    pub name: Option<&'a Name>,
+}
+
+/// Zero small values and rescale the rest so the output still covers the full range
+pub(crate) fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= deadzone || deadzone >= 1.0 {
+        return 0.0;
+    }
+    value.signum() * ((magnitude - deadzone) / (1.0 - deadzone)).min(1.0)
+}
+
+/// Same as `apply_deadzone` but on the length of a stick, so diagonals aren't squashed
+pub(crate) fn apply_radial_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let length = stick.length();
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+    stick / length * apply_deadzone(length, deadzone)
+}
@@ -1,9 +1,36 @@
+use std::path::Path;
 use bevy::prelude::*;
 
+use crate::camera::LayerViews;
+use crate::core::actions::{ActionMap, AxisBinding, ButtonPhase, InputBinding};
+use crate::core::gamepad::{apply_deadzone, apply_radial_deadzone, ConnectedGamepad, GamepadConfig, GamepadId};
+
+/// A finger currently on the touch screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchPoint {
+    pub id: u64,
+    /// Position in window pixels (0,0 is the top left)
+    pub screen_pos: Vec2,
+    /// Position in World Space (0,0 is center of screen)
+    pub world_pos: Vec2,
+    /// True only on the frame the finger touched down
+    pub just_pressed: bool,
+}
+
 pub struct InputContext<'a> {
     pub(crate) keys: &'a ButtonInput<KeyCode>,
     pub(crate) mouse_buttons: &'a ButtonInput<MouseButton>,
-    pub(crate) cursor_world_pos: Vec2, // We calculate this once per frame in lib.rs
+    pub(crate) gamepads: Vec<ConnectedGamepad<'a>>,
+    pub(crate) gamepads_connected: Vec<GamepadId>,
+    pub(crate) gamepads_disconnected: Vec<GamepadId>,
+    pub(crate) gamepad_config: &'a mut GamepadConfig,
+    pub(crate) actions: &'a mut ActionMap,
+    pub(crate) cameras: &'a LayerViews,
+    pub(crate) cursor_screen_pos: Option<Vec2>,
+    pub(crate) mouse_delta: Vec2,
+    pub(crate) mouse_wheel: Vec2,
+    pub(crate) text_typed: String,
+    pub(crate) touches: Vec<TouchPoint>,
 }
 
 impl<'a> InputContext<'a> {
@@ -23,9 +50,35 @@ impl<'a> InputContext<'a> {
         self.keys.just_released(key)
     }
 
-    /// Returns the mouse position in World Space (0,0 is center of screen)
+    /// Returns the mouse position in World Space on layer 0 (0,0 is center of screen).
+    /// With `Breeze::virtual_resolution` this is in virtual units, not window pixels.
     pub fn mouse_pos(&self) -> Vec2 {
-        self.cursor_world_pos
+        self.mouse_pos_on_layer(0).unwrap_or(Vec2::ZERO)
+    }
+
+    /// Returns the mouse position in World Space as seen by a layer's 2D camera,
+    /// or None if the cursor is outside the window or the layer has no camera.
+    /// The position is worked out from the layer's own viewport; use `mouse_over_layer` to check the cursor is inside it.
+    pub fn mouse_pos_on_layer(&self, layer: usize) -> Option<Vec2> {
+        let screen_pos = self.cursor_screen_pos?;
+        self.cameras.window_view(layer)?.screen_to_world(screen_pos)
+    }
+
+    /// Returns the ray from a layer's 3D camera through the mouse cursor,
+    /// or None if the cursor is outside the window or the layer has no camera.
+    /// Combine with `ray_plane_intersection` to find the point on the ground under the cursor.
+    pub fn mouse_ray(&self, layer: usize) -> Option<Ray3d> {
+        let screen_pos = self.cursor_screen_pos?;
+        self.cameras.window_view(layer)?.screen_to_ray(screen_pos)
+    }
+
+    /// Returns true if the cursor is inside the part of the window a layer draws into,
+    /// e.g. to find which split-screen view it is over
+    pub fn mouse_over_layer(&self, layer: usize) -> bool {
+        let Some(screen_pos) = self.cursor_screen_pos else {
+            return false;
+        };
+        self.cameras.window_view(layer).is_some_and(|camera| camera.contains(screen_pos))
     }
 
     /// Returns true while the mouse button is held down
@@ -37,4 +90,195 @@ impl<'a> InputContext<'a> {
     pub fn mouse_pressed(&self, button: MouseButton) -> bool {
         self.mouse_buttons.just_pressed(button)
     }
-}
\ No newline at end of file
+
+    /// Returns true only on the frame the mouse button was released
+    pub fn mouse_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.just_released(button)
+    }
+
+    /// Returns the mouse position in window pixels (0,0 is the top left),
+    /// or None if the cursor is outside the window
+    pub fn mouse_screen_pos(&self) -> Option<Vec2> {
+        self.cursor_screen_pos
+    }
+
+    /// Returns how far the mouse moved this frame, in raw device units.
+    /// Keeps reporting movement when the cursor is grabbed or at the edge of the screen.
+    pub fn mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+
+    /// Returns how far the scroll wheel moved this frame.
+    /// Positive y is scrolling up, positive x is scrolling right.
+    pub fn mouse_wheel(&self) -> Vec2 {
+        self.mouse_wheel
+    }
+
+    /// Returns the text typed this frame, including IME input.
+    /// Control characters are left out, so check `KeyCode::Backspace` and `KeyCode::Enter` separately.
+    pub fn text_typed(&self) -> &str {
+        &self.text_typed
+    }
+
+    /// Returns every finger currently on the touch screen
+    pub fn touches(&self) -> &[TouchPoint] {
+        &self.touches
+    }
+
+    /// Returns the gamepads that are currently connected
+    pub fn gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.iter().map(|gamepad| gamepad.id).collect()
+    }
+
+    /// Returns the gamepads that were connected this frame
+    pub fn gamepads_connected(&self) -> &[GamepadId] {
+        &self.gamepads_connected
+    }
+
+    /// Returns the gamepads that were disconnected this frame
+    pub fn gamepads_disconnected(&self) -> &[GamepadId] {
+        &self.gamepads_disconnected
+    }
+
+    /// Returns the name the OS reports for the gamepad, if it is connected
+    pub fn gamepad_name(&self, id: GamepadId) -> Option<&str> {
+        self.gamepads.iter()
+            .find(|gamepad| gamepad.id == id)
+            .and_then(|gamepad| gamepad.name)
+            .map(Name::as_str)
+    }
+
+    /// Returns true while the gamepad button is held down
+    pub fn gamepad_button_down(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_button_phase(id, button, ButtonPhase::Down)
+    }
+
+    /// Returns true only on the frame the gamepad button was pressed
+    pub fn gamepad_button_pressed(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_button_phase(id, button, ButtonPhase::Pressed)
+    }
+
+    /// Returns true only on the frame the gamepad button was released
+    pub fn gamepad_button_released(&self, id: GamepadId, button: GamepadButton) -> bool {
+        self.gamepad_button_phase(id, button, ButtonPhase::Released)
+    }
+
+    /// Returns the value of a single stick axis from -1.0 to 1.0, after the stick dead zone
+    pub fn gamepad_axis(&self, id: GamepadId, axis: GamepadAxis) -> f32 {
+        let value = self.gamepad(id).and_then(|pad| pad.get(axis)).unwrap_or(0.0);
+        apply_deadzone(value, self.gamepad_config.stick_deadzone)
+    }
+
+    /// Returns the left stick position, after the stick dead zone
+    pub fn left_stick(&self, id: GamepadId) -> Vec2 {
+        let stick = self.gamepad(id).map(Gamepad::left_stick).unwrap_or_default();
+        apply_radial_deadzone(stick, self.gamepad_config.stick_deadzone)
+    }
+
+    /// Returns the right stick position, after the stick dead zone
+    pub fn right_stick(&self, id: GamepadId) -> Vec2 {
+        let stick = self.gamepad(id).map(Gamepad::right_stick).unwrap_or_default();
+        apply_radial_deadzone(stick, self.gamepad_config.stick_deadzone)
+    }
+
+    /// Returns how far the left trigger is pulled (0.0 to 1.0), after the trigger dead zone
+    pub fn left_trigger(&self, id: GamepadId) -> f32 {
+        let value = self.gamepad(id).and_then(|pad| pad.get(GamepadButton::LeftTrigger2)).unwrap_or(0.0);
+        apply_deadzone(value, self.gamepad_config.trigger_deadzone)
+    }
+
+    /// Returns how far the right trigger is pulled (0.0 to 1.0), after the trigger dead zone
+    pub fn right_trigger(&self, id: GamepadId) -> f32 {
+        let value = self.gamepad(id).and_then(|pad| pad.get(GamepadButton::RightTrigger2)).unwrap_or(0.0);
+        apply_deadzone(value, self.gamepad_config.trigger_deadzone)
+    }
+
+    /// Change the stick and trigger dead zones (0.0 to 1.0)
+    pub fn set_gamepad_deadzones(&mut self, stick: f32, trigger: f32) {
+        self.gamepad_config.stick_deadzone = stick.clamp(0.0, 1.0);
+        self.gamepad_config.trigger_deadzone = trigger.clamp(0.0, 1.0);
+    }
+
+    /// Bind a named action to one or more buttons, replacing any previous bindings.
+    /// e.g. `bind_action("jump", [KeyCode::Space.into(), GamepadButton::South.into()])`
+    pub fn bind_action(&mut self, name: &str, bindings: impl IntoIterator<Item = InputBinding>) {
+        self.actions.bind_action(name, bindings);
+    }
+
+    /// Bind a named axis to one or more sources, replacing any previous bindings.
+    /// e.g. `bind_axis("move_x", [AxisBinding::keys(KeyCode::KeyA, KeyCode::KeyD), GamepadAxis::LeftStickX.into()])`
+    pub fn bind_axis(&mut self, name: &str, bindings: impl IntoIterator<Item = AxisBinding>) {
+        self.actions.bind_axis(name, bindings);
+    }
+
+    /// Remove a named action or axis
+    pub fn unbind(&mut self, name: &str) {
+        self.actions.unbind(name);
+    }
+
+    /// Get all current bindings
+    pub fn actions(&self) -> &ActionMap {
+        self.actions
+    }
+
+    /// Returns true while any button bound to the action is held down
+    pub fn action_down(&self, name: &str) -> bool {
+        self.action_phase(name, ButtonPhase::Down)
+    }
+
+    /// Returns true only on the frame a button bound to the action was pressed
+    pub fn action_pressed(&self, name: &str) -> bool {
+        self.action_phase(name, ButtonPhase::Pressed)
+    }
+
+    /// Returns true only on the frame a button bound to the action was released
+    pub fn action_released(&self, name: &str) -> bool {
+        self.action_phase(name, ButtonPhase::Released)
+    }
+
+    /// Returns the value of a named axis from -1.0 to 1.0.
+    /// If several sources are active, the one furthest from zero wins.
+    pub fn axis(&self, name: &str) -> f32 {
+        self.actions.axis_bindings(name).iter()
+            .map(|binding| match *binding {
+                AxisBinding::Keys { negative, positive } => {
+                    self.keys.pressed(positive) as i32 as f32 - self.keys.pressed(negative) as i32 as f32
+                }
+                AxisBinding::Gamepad(axis) => {
+                    self.gamepads.iter()
+                        .map(|gamepad| self.gamepad_axis(gamepad.id, axis))
+                        .fold(0.0, |best: f32, v| if v.abs() > best.abs() { v } else { best })
+                }
+            })
+            .fold(0.0, |best: f32, v| if v.abs() > best.abs() { v } else { best })
+            .clamp(-1.0, 1.0)
+    }
+
+    /// Save the current bindings to a config file so players can keep their controls
+    pub fn save_bindings(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.actions.to_config_string())
+    }
+
+    /// Load bindings from a config file written by `save_bindings`, replacing all current bindings
+    pub fn load_bindings(&mut self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let text = std::fs::read_to_string(path)?;
+        *self.actions = ActionMap::from_config_str(&text)?;
+        Ok(())
+    }
+
+    fn action_phase(&self, name: &str, phase: ButtonPhase) -> bool {
+        self.actions.action_bindings(name).iter().any(|binding| match *binding {
+            InputBinding::Key(key) => phase.check(self.keys, key),
+            InputBinding::Mouse(button) => phase.check(self.mouse_buttons, button),
+            InputBinding::Gamepad(button) => self.gamepads.iter().any(|gamepad| phase.check(gamepad.pad.digital(), button)),
+        })
+    }
+
+    fn gamepad(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.iter().find(|gamepad| gamepad.id == id).map(|gamepad| gamepad.pad)
+    }
+
+    fn gamepad_button_phase(&self, id: GamepadId, button: GamepadButton, phase: ButtonPhase) -> bool {
+        self.gamepad(id).is_some_and(|pad| phase.check(pad.digital(), button))
+    }
+}
@@ -1,6 +1,12 @@
+pub mod actions;
 pub mod audio;
 pub mod input;
+pub mod replay;
 pub mod window;
 pub mod fps;
+pub mod gamepad;
 pub mod scene;
-pub mod system;
\ No newline at end of file
+pub mod synth;
+pub mod system;
+pub mod timestep;
+pub mod transition;
\ No newline at end of file
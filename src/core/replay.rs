@@ -0,0 +1,379 @@
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::reflect::Typed;
+
+use crate::core::actions::parse_variant;
+
+const HEADER: &str = "breeze-replay 1";
+
+/// How many frames to buffer before writing them to disk
+const FLUSH_INTERVAL: u32 = 60;
+
+/// Everything the scenes saw from the keyboard and mouse during one frame
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    pub delta: Duration,
+    /// Keys held down at the end of the frame
+    pub keys: Vec<KeyCode>,
+    /// Keys pressed this frame, including taps that were released again within it
+    pub keys_pressed: Vec<KeyCode>,
+    pub keys_released: Vec<KeyCode>,
+    pub mouse_buttons: Vec<MouseButton>,
+    pub mouse_pressed: Vec<MouseButton>,
+    pub mouse_released: Vec<MouseButton>,
+    pub cursor: Option<Vec2>,
+    pub mouse_delta: Vec2,
+    pub mouse_wheel: Vec2,
+    pub text: String,
+}
+
+impl InputFrame {
+    /// One line per frame, leaving out anything that is empty, e.g.
+    /// `16666667 k:KeyA,Space kp:Space m:Left c:400,300`
+    fn to_line(&self) -> String {
+        let mut line = self.delta.as_nanos().to_string();
+
+        push_keys(&mut line, "k", &self.keys);
+        push_keys(&mut line, "kp", &self.keys_pressed);
+        push_keys(&mut line, "kr", &self.keys_released);
+        push_list(&mut line, "m", &self.mouse_buttons);
+        push_list(&mut line, "mp", &self.mouse_pressed);
+        push_list(&mut line, "mr", &self.mouse_released);
+        if let Some(cursor) = self.cursor {
+            line.push_str(&format!(" c:{},{}", cursor.x, cursor.y));
+        }
+        if self.mouse_delta != Vec2::ZERO {
+            line.push_str(&format!(" d:{},{}", self.mouse_delta.x, self.mouse_delta.y));
+        }
+        if self.mouse_wheel != Vec2::ZERO {
+            line.push_str(&format!(" w:{},{}", self.mouse_wheel.x, self.mouse_wheel.y));
+        }
+        if !self.text.is_empty() {
+            // Hex keeps spaces and commas in typed text from breaking the line format
+            let hex: String = self.text.bytes().map(|b| format!("{b:02x}")).collect();
+            line.push_str(&format!(" t:{hex}"));
+        }
+
+        line
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut parts = line.split(' ');
+        let mut frame = InputFrame {
+            delta: Duration::from_nanos(parts.next()?.parse().ok()?),
+            ..default()
+        };
+
+        for part in parts {
+            let (tag, value) = part.split_once(':')?;
+            match tag {
+                "k" => frame.keys = parse_list(value)?,
+                "kp" => frame.keys_pressed = parse_list(value)?,
+                "kr" => frame.keys_released = parse_list(value)?,
+                "m" => frame.mouse_buttons = parse_list(value)?,
+                "mp" => frame.mouse_pressed = parse_list(value)?,
+                "mr" => frame.mouse_released = parse_list(value)?,
+                "c" => frame.cursor = Some(parse_vec2(value)?),
+                "d" => frame.mouse_delta = parse_vec2(value)?,
+                "w" => frame.mouse_wheel = parse_vec2(value)?,
+                "t" => {
+                    let bytes = (0..value.len()).step_by(2)
+                        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+                        .collect::<Option<Vec<u8>>>()?;
+                    frame.text = String::from_utf8(bytes).ok()?;
+                }
+                _ => return None,
+            }
+        }
+
+        Some(frame)
+    }
+}
+
+/// Append ` tag:A,B` to the line, if there is anything to write
+fn push_list<T: Debug>(line: &mut String, tag: &str, items: &[T]) {
+    if !items.is_empty() {
+        let names: Vec<String> = items.iter().map(|item| format!("{item:?}")).collect();
+        line.push_str(&format!(" {tag}:{}", names.join(",")));
+    }
+}
+
+/// Like `push_list`, leaving out keys with no name (`KeyCode::Unidentified`), which can't be read back
+fn push_keys(line: &mut String, tag: &str, keys: &[KeyCode]) {
+    let keys: Vec<KeyCode> = keys.iter().filter(|key| !matches!(key, KeyCode::Unidentified(_))).copied().collect();
+    push_list(line, tag, &keys);
+}
+
+fn parse_list<T: FromReflect + Typed>(value: &str) -> Option<Vec<T>> {
+    value.split(',').map(parse_variant).collect()
+}
+
+fn parse_vec2(value: &str) -> Option<Vec2> {
+    let (x, y) = value.split_once(',')?;
+    Some(Vec2::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+/// A recorded sequence of input frames that can be played back with `Breeze::replay_input`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    pub frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    /// Load a recording written with `Breeze::record_input`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut lines = text.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a breeze input recording"));
+        }
+
+        let frames = lines
+            .enumerate()
+            .map(|(index, line)| InputFrame::from_line(line).ok_or_else(|| io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: invalid input frame: {}", index + 2, line),
+            )))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        Ok(Self { frames })
+    }
+
+    /// Save the recording to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "{HEADER}")?;
+        for frame in &self.frames {
+            writeln!(writer, "{}", frame.to_line())?;
+        }
+        writer.flush()
+    }
+}
+
+/// Writes each frame's input to disk as it happens, flushing every second or so,
+/// so most of a recording survives a crash
+#[derive(Resource)]
+pub struct InputRecorder {
+    writer: Option<BufWriter<File>>,
+    unflushed: u32,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path) -> Self {
+        let writer = File::create(path)
+            .map(BufWriter::new)
+            .and_then(|mut writer| writeln!(writer, "{HEADER}").map(|_| writer));
+
+        match writer {
+            Ok(writer) => Self { writer: Some(writer), unflushed: 0 },
+            Err(err) => {
+                error!("Could not create input recording {}: {}", path.display(), err);
+                Self { writer: None, unflushed: 0 }
+            }
+        }
+    }
+
+    pub fn record(&mut self, frame: &InputFrame) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        self.unflushed += 1;
+        let result = writeln!(writer, "{}", frame.to_line()).and_then(|_| {
+            if self.unflushed < FLUSH_INTERVAL {
+                return Ok(());
+            }
+            self.unflushed = 0;
+            writer.flush()
+        });
+
+        if let Err(err) = result {
+            error!("Stopped recording input: {}", err);
+            self.writer = None;
+        }
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        if let Some(writer) = self.writer.as_mut()
+            && let Err(err) = writer.flush()
+        {
+            error!("Could not finish input recording: {}", err);
+        }
+    }
+}
+
+/// Feeds recorded frames to the scenes in place of live input
+#[derive(Resource)]
+pub struct InputReplay {
+    frames: Vec<InputFrame>,
+    next: usize,
+    pub(crate) time: Time,
+    pub(crate) keys: ButtonInput<KeyCode>,
+    pub(crate) mouse_buttons: ButtonInput<MouseButton>,
+}
+
+impl InputReplay {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            frames: recording.frames,
+            next: 0,
+            time: Time::default(),
+            keys: ButtonInput::default(),
+            mouse_buttons: ButtonInput::default(),
+        }
+    }
+
+    /// Returns true once every recorded frame has been played
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.frames.len()
+    }
+
+    /// Move on to the next recorded frame, updating the replayed time and buttons
+    pub fn advance(&mut self) -> Option<InputFrame> {
+        let frame = self.frames.get(self.next)?.clone();
+        self.next += 1;
+
+        self.time.advance_by(frame.delta);
+        replay_buttons(&mut self.keys, &frame.keys, &frame.keys_pressed, &frame.keys_released);
+        replay_buttons(&mut self.mouse_buttons, &frame.mouse_buttons, &frame.mouse_pressed, &frame.mouse_released);
+
+        Some(frame)
+    }
+}
+
+/// Replay one frame of presses and releases, so taps within the frame still count as just pressed,
+/// then make sure the held set matches `down`
+fn replay_buttons<T: Copy + Eq + std::hash::Hash + Send + Sync + 'static>(input: &mut ButtonInput<T>, down: &[T], pressed: &[T], released: &[T]) {
+    input.clear();
+
+    // Release before pressing, so a held button pressed again this frame is both
+    for &button in released {
+        input.release(button);
+    }
+    for &button in pressed {
+        input.press(button);
+    }
+    for button in released.iter().filter(|b| !down.contains(b)) {
+        input.release(*button);
+    }
+
+    let stale: Vec<T> = input.get_pressed().filter(|b| !down.contains(b)).copied().collect();
+    for button in stale {
+        input.release(button);
+    }
+    for &button in down {
+        input.press(button);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_frame_is_just_the_delta() {
+        let frame = InputFrame { delta: Duration::from_nanos(16_666_667), ..default() };
+        assert_eq!(frame.to_line(), "16666667");
+        assert_eq!(InputFrame::from_line("16666667"), Some(frame));
+    }
+
+    #[test]
+    fn frames_round_trip() {
+        let frame = InputFrame {
+            delta: Duration::from_nanos(16_666_667),
+            keys: vec![KeyCode::KeyA, KeyCode::Space],
+            keys_pressed: vec![KeyCode::Space, KeyCode::Enter],
+            keys_released: vec![KeyCode::Enter],
+            mouse_buttons: vec![MouseButton::Left, MouseButton::Other(5)],
+            mouse_pressed: vec![MouseButton::Left],
+            mouse_released: vec![MouseButton::Right],
+            cursor: Some(Vec2::new(400.5, -0.25)),
+            mouse_delta: Vec2::new(1.0, -2.0),
+            mouse_wheel: Vec2::new(0.0, 3.0),
+            text: "hi, wörld".to_string(),
+        };
+
+        let line = frame.to_line();
+        assert!(!line.contains("wörld"), "{line}");
+        assert_eq!(InputFrame::from_line(&line), Some(frame));
+    }
+
+    #[test]
+    fn unidentified_keys_are_left_out() {
+        use bevy::input::keyboard::NativeKeyCode;
+
+        let unidentified = KeyCode::Unidentified(NativeKeyCode::Xkb(38));
+        let frame = InputFrame {
+            delta: Duration::from_nanos(16_666_667),
+            keys: vec![KeyCode::KeyA, unidentified],
+            keys_pressed: vec![unidentified],
+            ..default()
+        };
+
+        let read_back = InputFrame::from_line(&frame.to_line()).expect("the line can be read back");
+        assert_eq!(read_back.keys, vec![KeyCode::KeyA]);
+        assert!(read_back.keys_pressed.is_empty());
+    }
+
+    #[test]
+    fn bad_lines_are_rejected() {
+        assert_eq!(InputFrame::from_line(""), None);
+        assert_eq!(InputFrame::from_line("16666667 k:NotAKey"), None);
+        assert_eq!(InputFrame::from_line("16666667 c:1"), None);
+        assert_eq!(InputFrame::from_line("16666667 t:zz"), None);
+        assert_eq!(InputFrame::from_line("16666667 q:1"), None);
+    }
+
+    #[test]
+    fn replay_tracks_presses_and_releases() {
+        let frames = [vec![KeyCode::Space], vec![KeyCode::Space], vec![]]
+            .into_iter()
+            .map(|keys| InputFrame { delta: Duration::from_millis(10), keys, ..default() })
+            .collect();
+        let mut replay = InputReplay::new(InputRecording { frames });
+
+        replay.advance();
+        assert!(replay.keys.just_pressed(KeyCode::Space));
+        replay.advance();
+        assert!(replay.keys.pressed(KeyCode::Space) && !replay.keys.just_pressed(KeyCode::Space));
+        replay.advance();
+        assert!(replay.keys.just_released(KeyCode::Space));
+
+        assert!(replay.is_finished());
+        assert_eq!(replay.advance(), None);
+        assert_eq!(replay.time.elapsed(), Duration::from_millis(30));
+    }
+
+    #[test]
+    fn replay_keeps_taps_within_a_frame() {
+        let tap = InputFrame {
+            keys_pressed: vec![KeyCode::Space],
+            keys_released: vec![KeyCode::Space],
+            ..default()
+        };
+        let repress = InputFrame {
+            keys: vec![KeyCode::KeyA],
+            keys_pressed: vec![KeyCode::KeyA],
+            keys_released: vec![KeyCode::KeyA],
+            ..default()
+        };
+        let hold = InputFrame { keys: vec![KeyCode::KeyA], keys_pressed: vec![KeyCode::KeyA], ..default() };
+        let mut replay = InputReplay::new(InputRecording { frames: vec![tap, hold, repress] });
+
+        replay.advance();
+        assert!(replay.keys.just_pressed(KeyCode::Space) && replay.keys.just_released(KeyCode::Space));
+        assert!(!replay.keys.pressed(KeyCode::Space));
+
+        replay.advance();
+        replay.advance();
+        assert!(replay.keys.just_pressed(KeyCode::KeyA) && replay.keys.just_released(KeyCode::KeyA));
+        assert!(replay.keys.pressed(KeyCode::KeyA));
+    }
+}
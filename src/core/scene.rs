@@ -1,4 +1,8 @@
+use std::any::Any;
+use bevy::log::warn;
+use crate::camera::CameraMode;
 use crate::context::{Context, DrawContext};
+use crate::core::transition::{ActiveTransition, Transition, TRANSITION_LAYER};
 
 /// 1. The States
 pub enum SceneTransition {
@@ -9,35 +13,123 @@ pub enum SceneTransition {
     /// Switch the current scene with a new one
     Switch(Box<dyn Scene>),
 
+    /// Switch the current scene with a new one using an animated transition
+    SwitchWith(Box<dyn Scene>, Transition),
+
     /// Push a new scene onto the stack
     Push(Box<dyn Scene>),
 
+    /// Push a new scene onto the stack using an animated transition
+    PushWith(Box<dyn Scene>, Transition),
+
     /// Pop the current scene off the stack
     Pop,
 
+    /// Pop the current scene and hand a result to the scene underneath
+    PopWith(SceneResult),
+
+    /// Pop the current scene off the stack using an animated transition
+    PopWithTransition(Transition),
+
+    /// Pop the top `n` scenes off the stack. The bottom scene is never popped,
+    /// use `Quit` to leave the application.
+    PopN(usize),
+
+    /// Pop every scene except the bottom one
+    PopToRoot,
+
+    /// Exit every scene on the stack and start again with a new one
+    ReplaceAll(Box<dyn Scene>),
+
     /// Quit the application
     Quit,
 }
 
+impl SceneTransition {
+    /// Pop the current scene and pass `value` to the `on_resume` of the scene underneath
+    pub fn pop_with<T: Any + Send + Sync>(value: T) -> Self {
+        SceneTransition::PopWith(SceneResult(Box::new(value)))
+    }
+}
+
+/// A value returned by a popped scene (e.g. the option chosen in a dialog)
+pub struct SceneResult(Box<dyn Any + Send + Sync>);
+
+impl SceneResult {
+    /// Check whether the result holds a value of type `T`
+    pub fn is<T: Any>(&self) -> bool {
+        self.0.is::<T>()
+    }
+
+    /// Take the value out if it is of type `T`, otherwise hand the result back
+    pub fn downcast<T: Any>(self) -> Result<T, SceneResult> {
+        match self.0.downcast::<T>() {
+            Ok(value) => Ok(*value),
+            Err(other) => Err(SceneResult(other)),
+        }
+    }
+}
+
 /// 2. The Interface
-pub trait Scene: Send + Sync {
+pub trait Scene: Any + Send + Sync {
 
     /// Init is called when the scene is first added to the stack
     fn init(&mut self, _ctx: &mut Context) {}
 
-    /// Update is called only for the top-most scene in the stack
+    /// Called when another scene is pushed on top of this one
+    fn on_pause(&mut self, _ctx: &mut Context) {}
+
+    /// Called when this scene becomes the top-most scene again.
+    /// `result` holds the value of a `SceneTransition::PopWith` from the scene above.
+    fn on_resume(&mut self, _ctx: &mut Context, _result: Option<SceneResult>) {}
+
+    /// Called when the scene is popped, switched away from, or the application quits
+    fn on_exit(&mut self, _ctx: &mut Context) {}
+
+    /// Update is called for the top-most scene in the stack, and for the scenes
+    /// beneath it until one of them is modal
     fn update(&mut self, _ctx: &mut Context) -> SceneTransition {
         SceneTransition::None
     }
 
-    /// Draw is called for all scenes in the stack, from bottom to top
+    /// Fixed update is called for the same scenes as `update`, zero or more times per frame,
+    /// at the rate set with `Breeze::fixed_timestep`. Runs before `update`.
+    fn fixed_update(&mut self, _ctx: &mut Context) {}
+
+    /// Draw is called for all scenes in the stack, from bottom to top,
+    /// starting at the top-most opaque scene
     fn draw(&mut self, ctx: &mut DrawContext);
+
+    /// An opaque scene covers the whole screen, so scenes beneath it are not drawn
+    fn is_opaque(&self) -> bool {
+        false
+    }
+
+    /// A modal scene stops the scenes beneath it from updating.
+    /// Return false for overlays (e.g. a pause menu over a running simulation)
+    /// that should let the scene underneath keep running. Only the top-most scene
+    /// can change the stack, so transitions returned from beneath an overlay are ignored.
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
+
+/// Index of the lowest scene that is visible, i.e. the top-most opaque scene
+pub(crate) fn first_visible(stack: &[Box<dyn Scene>]) -> usize {
+    stack.iter().rposition(|scene| scene.is_opaque()).unwrap_or(0)
+}
+
+/// Index of the lowest scene that should be updated, i.e. the top-most modal scene
+fn first_updated(stack: &[Box<dyn Scene>]) -> usize {
+    stack.iter().rposition(|scene| scene.is_modal()).unwrap_or(0)
 }
 
 /// 3. The Logic (SceneManager)
 pub struct SceneManager {
     pub(crate) stack: Vec<Box<dyn Scene>>,
     pub(crate) should_quit: bool,
+    transition: Option<ActiveTransition>,
+    release_transition_layer: bool,
 }
 
 impl SceneManager {
@@ -45,13 +137,66 @@ impl SceneManager {
         Self {
             stack: vec![Box::new(initial_scene)],
             should_quit: false,
+            transition: None,
+            release_transition_layer: false,
+        }
+    }
+
+    /// Number of scenes currently on the stack
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns true while an animated transition is playing
+    pub fn is_transitioning(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Returns true once a scene has requested `SceneTransition::Quit`
+    pub fn is_quitting(&self) -> bool {
+        self.should_quit
+    }
+
+    /// Get the top-most scene if it is of type `T`
+    pub fn current<T: Scene>(&self) -> Option<&T> {
+        let scene: &dyn Any = self.stack.last()?.as_ref();
+        scene.downcast_ref::<T>()
+    }
+
+    /// Get the top-most scene mutably if it is of type `T`
+    pub fn current_mut<T: Scene>(&mut self) -> Option<&mut T> {
+        let scene: &mut dyn Any = self.stack.last_mut()?.as_mut();
+        scene.downcast_mut::<T>()
+    }
+
+    /// Helper to run a fixed step on the top-most scene and any scenes running beneath it
+    pub fn fixed_update(&mut self, ctx: &mut Context) {
+        let start = first_updated(&self.stack);
+        for scene in self.stack[start..].iter_mut() {
+            scene.fixed_update(ctx);
         }
     }
 
     /// Helper to handle the update of the top-most scene
     pub fn update(&mut self, ctx: &mut Context) {
-        // We only update the active (top) scene
-        let transition = if let Some(active_scene) = self.stack.last_mut() {
+        // Advance any transition in progress
+        if let Some(transition) = self.transition.as_mut() {
+            transition.elapsed += ctx.time.delta_secs();
+            if transition.is_finished() {
+                self.transition = None;
+                self.release_transition_layer = true;
+            }
+        }
+
+        // Scenes beneath non-modal overlays keep running, but only
+        // the active (top) scene can change the stack
+        let start = first_updated(&self.stack);
+        let transition = if let Some((active_scene, below)) = self.stack[start..].split_last_mut() {
+            for scene in below.iter_mut() {
+                if !matches!(scene.update(ctx), SceneTransition::None) {
+                    warn!("Ignored a scene transition requested from beneath a non-modal scene");
+                }
+            }
             active_scene.update(ctx)
         } else {
             SceneTransition::None
@@ -60,28 +205,132 @@ impl SceneManager {
         // Handle the result immediately
         match transition {
             SceneTransition::None => {}
-            SceneTransition::Switch(mut new_scene) => {
-                new_scene.init(ctx);
-                self.stack.pop();
-                self.stack.push(new_scene);
+            SceneTransition::Switch(new_scene) => {
+                self.switch(ctx, new_scene);
             }
-            SceneTransition::Push(mut new_scene) => {
-                new_scene.init(ctx);
-                self.stack.push(new_scene);
+            SceneTransition::SwitchWith(new_scene, effect) => {
+                let outgoing = self.switch(ctx, new_scene);
+                self.start_transition(effect, outgoing, true);
+            }
+            SceneTransition::Push(new_scene) => {
+                self.push(ctx, new_scene);
+            }
+            SceneTransition::PushWith(new_scene, effect) => {
+                self.push(ctx, new_scene);
+                self.start_transition(effect, None, true);
             }
             SceneTransition::Pop => {
-                self.stack.pop();
+                self.pop(ctx, None);
+            }
+            SceneTransition::PopWith(result) => {
+                self.pop(ctx, Some(result));
+            }
+            SceneTransition::PopWithTransition(effect) => {
+                let outgoing = self.pop(ctx, None);
+                self.start_transition(effect, outgoing, false);
+            }
+            SceneTransition::PopN(n) => {
+                self.pop_n(ctx, n);
+            }
+            SceneTransition::PopToRoot => {
+                self.pop_n(ctx, self.stack.len().saturating_sub(1));
+            }
+            SceneTransition::ReplaceAll(mut new_scene) => {
+                for mut old_scene in self.stack.drain(..).rev() {
+                    old_scene.on_exit(ctx);
+                }
+                new_scene.init(ctx);
+                self.stack.push(new_scene);
             }
             SceneTransition::Quit => {
-                self.should_quit = true;
+                self.quit(ctx);
             }
         }
     }
 
-    /// Helper to handle drawing all scenes (Painter's Algorithm)
+    /// Give every scene the chance to clean up, top to bottom, then stop
+    pub(crate) fn quit(&mut self, ctx: &mut Context) {
+        for scene in self.stack.iter_mut().rev() {
+            scene.on_exit(ctx);
+        }
+        self.should_quit = true;
+    }
+
+    /// Replace the top-most scene, returning the old one
+    fn switch(&mut self, ctx: &mut Context, mut new_scene: Box<dyn Scene>) -> Option<Box<dyn Scene>> {
+        let mut old_scene = self.stack.pop();
+        if let Some(old_scene) = old_scene.as_mut() {
+            old_scene.on_exit(ctx);
+        }
+        new_scene.init(ctx);
+        self.stack.push(new_scene);
+        old_scene
+    }
+
+    /// Pause the top-most scene and push a new one over it
+    fn push(&mut self, ctx: &mut Context, mut new_scene: Box<dyn Scene>) {
+        if let Some(active_scene) = self.stack.last_mut() {
+            active_scene.on_pause(ctx);
+        }
+        new_scene.init(ctx);
+        self.stack.push(new_scene);
+    }
+
+    /// Pop the top-most scene and resume the one underneath, returning the popped scene
+    fn pop(&mut self, ctx: &mut Context, result: Option<SceneResult>) -> Option<Box<dyn Scene>> {
+        let mut old_scene = self.stack.pop();
+        if let Some(old_scene) = old_scene.as_mut() {
+            old_scene.on_exit(ctx);
+        }
+        if let Some(active_scene) = self.stack.last_mut() {
+            active_scene.on_resume(ctx, result);
+        }
+        old_scene
+    }
+
+    /// Pop up to `n` scenes, keeping the bottom one, then resume whichever scene is left on top
+    fn pop_n(&mut self, ctx: &mut Context, n: usize) {
+        let keep = self.stack.len().saturating_sub(n).max(1);
+        if keep >= self.stack.len() {
+            return;
+        }
+
+        for mut old_scene in self.stack.drain(keep..).rev() {
+            old_scene.on_exit(ctx);
+        }
+        if let Some(active_scene) = self.stack.last_mut() {
+            active_scene.on_resume(ctx, None);
+        }
+    }
+
+    /// Keep the outgoing scene alive so it can be drawn while the effect plays.
+    /// Starting a new transition cuts short any transition already playing.
+    fn start_transition(&mut self, effect: Transition, outgoing: Option<Box<dyn Scene>>, incoming_on_top: bool) {
+        self.transition = Some(ActiveTransition {
+            effect,
+            elapsed: 0.0,
+            outgoing,
+            incoming_on_top,
+        });
+        self.release_transition_layer = false;
+    }
+
+    /// Helper to handle drawing all visible scenes (Painter's Algorithm)
     pub fn draw(&mut self, ctx: &mut DrawContext) {
-        for scene in self.stack.iter_mut() {
+        if let Some(transition) = self.transition.as_mut() {
+            transition.draw(&mut self.stack, ctx);
+            return;
+        }
+
+        let start = first_visible(&self.stack);
+        for scene in self.stack[start..].iter_mut() {
             scene.draw(ctx);
         }
+
+        // Remove the overlay camera once a transition has finished
+        if self.release_transition_layer {
+            ctx.with_layer(TRANSITION_LAYER, |overlay| overlay.set_camera(CameraMode::None));
+            self.release_transition_layer = false;
+        }
     }
 }
\ No newline at end of file
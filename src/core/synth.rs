@@ -0,0 +1,306 @@
+use std::f32::consts::TAU;
+use bevy::prelude::*;
+
+/// Sample rate of synthesized sounds
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// Number of random values noise cycles through per period (as in sfxr)
+const NOISE_LEN: usize = 32;
+
+/// The shape of a synthesized sound wave
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Waveform {
+    #[default]
+    Square,
+    Sawtooth,
+    Sine,
+    Triangle,
+    Noise,
+}
+
+/// The bit patterns of a `SfxParams`, which can be hashed unlike the floats themselves
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct SfxKey(Waveform, [u32; 12], u64);
+
+/// Settings for an sfxr-style synthesized sound effect.
+/// Times are in seconds and frequencies in Hz.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SfxParams {
+    pub waveform: Waveform,
+    pub volume: f32,
+    /// Starting frequency
+    pub freq: f32,
+    /// How fast the frequency changes, in octaves per second (negative slides down)
+    pub freq_slide: f32,
+    /// Vibrato strength as a fraction of the frequency (0.0 for none)
+    pub vibrato_depth: f32,
+    /// Vibrato speed
+    pub vibrato_speed: f32,
+    /// Multiply the frequency by this once `pitch_jump_time` has passed (1.0 for no jump)
+    pub pitch_jump: f32,
+    pub pitch_jump_time: f32,
+    /// Fraction of each period a square wave spends high (0.5 is a plain square)
+    pub duty: f32,
+    pub attack: f32,
+    pub sustain: f32,
+    /// Extra volume at the start of the sustain, fading to nothing by its end
+    pub sustain_punch: f32,
+    pub decay: f32,
+    /// Seed for the noise waveform, so the same settings always sound the same
+    pub seed: u64,
+}
+
+impl Default for SfxParams {
+    fn default() -> Self {
+        Self {
+            waveform: Waveform::Square,
+            volume: 0.5,
+            freq: 440.0,
+            freq_slide: 0.0,
+            vibrato_depth: 0.0,
+            vibrato_speed: 0.0,
+            pitch_jump: 1.0,
+            pitch_jump_time: 0.0,
+            duty: 0.5,
+            attack: 0.0,
+            sustain: 0.2,
+            sustain_punch: 0.0,
+            decay: 0.1,
+            seed: 0,
+        }
+    }
+}
+
+impl SfxParams {
+    /// A plain tone that plays for `secs`, with a short fade out to avoid a click.
+    pub fn tone(freq: f32, secs: f32, waveform: Waveform) -> Self {
+        let decay = (secs * 0.1).min(0.02);
+        Self {
+            waveform,
+            freq,
+            sustain: (secs - decay).max(0.0),
+            decay,
+            ..default()
+        }
+    }
+
+    /// A coin or item pickup: a short blip that jumps up in pitch.
+    /// The same seed always gives the same sound.
+    pub fn pickup(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        Self {
+            waveform: if rng.chance(0.5) { Waveform::Square } else { Waveform::Sawtooth },
+            freq: rng.range(600.0, 1400.0),
+            duty: rng.range(0.3, 0.5),
+            pitch_jump: rng.range(1.2, 1.8),
+            pitch_jump_time: rng.range(0.04, 0.1),
+            sustain: rng.range(0.03, 0.12),
+            sustain_punch: rng.range(0.3, 0.6),
+            decay: rng.range(0.1, 0.3),
+            seed,
+            ..default()
+        }
+    }
+
+    /// A rumbling explosion made from noise that slides down in pitch.
+    /// The same seed always gives the same sound.
+    pub fn explosion(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        Self {
+            waveform: Waveform::Noise,
+            freq: rng.range(200.0, 1200.0),
+            freq_slide: rng.range(-2.0, -0.5),
+            vibrato_depth: if rng.chance(0.5) { rng.range(0.1, 0.4) } else { 0.0 },
+            vibrato_speed: rng.range(5.0, 20.0),
+            sustain: rng.range(0.1, 0.3),
+            sustain_punch: rng.range(0.2, 0.6),
+            decay: rng.range(0.3, 0.6),
+            seed,
+            ..default()
+        }
+    }
+
+    /// Identifies these exact settings, so sounds synthesized from them can be reused
+    pub(crate) fn key(&self) -> SfxKey {
+        let floats = [
+            self.volume, self.freq, self.freq_slide, self.vibrato_depth, self.vibrato_speed, self.pitch_jump,
+            self.pitch_jump_time, self.duty, self.attack, self.sustain, self.sustain_punch, self.decay,
+        ];
+        SfxKey(self.waveform, floats.map(f32::to_bits), self.seed)
+    }
+
+    /// Total length in seconds
+    pub fn duration(&self) -> f32 {
+        self.attack.max(0.0) + self.sustain.max(0.0) + self.decay.max(0.0)
+    }
+
+    /// Generate mono samples from -1.0 to 1.0 at `sample_rate`
+    pub fn synthesize(&self, sample_rate: u32) -> Vec<f32> {
+        let rate = sample_rate as f32;
+        let (attack, sustain, decay) = (self.attack.max(0.0), self.sustain.max(0.0), self.decay.max(0.0));
+        let len = (self.duration() * rate).round() as usize;
+
+        let mut rng = Rng::new(self.seed);
+        let mut noise = [0.0; NOISE_LEN];
+        noise.iter_mut().for_each(|n| *n = rng.range(-1.0, 1.0));
+
+        let mut phase = 0.0;
+        let mut samples = Vec::with_capacity(len);
+
+        for i in 0..len {
+            let t = i as f32 / rate;
+
+            let envelope = if t < attack {
+                t / attack
+            } else if t < attack + sustain {
+                1.0 + self.sustain_punch * (1.0 - (t - attack) / sustain)
+            } else {
+                1.0 - (t - attack - sustain) / decay
+            };
+
+            let mut freq = self.freq * 2f32.powf(self.freq_slide * t);
+            if self.pitch_jump_time > 0.0 && t >= self.pitch_jump_time {
+                freq *= self.pitch_jump;
+            }
+            freq *= 1.0 + self.vibrato_depth * (TAU * self.vibrato_speed * t).sin();
+            // Stay below the Nyquist frequency
+            let freq = freq.clamp(0.0, rate / 2.0);
+
+            phase += freq / rate;
+            if phase >= 1.0 {
+                phase -= phase.floor();
+                if self.waveform == Waveform::Noise {
+                    noise.iter_mut().for_each(|n| *n = rng.range(-1.0, 1.0));
+                }
+            }
+
+            let wave = match self.waveform {
+                Waveform::Square => if phase < self.duty { 1.0 } else { -1.0 },
+                Waveform::Sawtooth => 1.0 - 2.0 * phase,
+                Waveform::Sine => (TAU * phase).sin(),
+                Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+                Waveform::Noise => noise[((phase * NOISE_LEN as f32) as usize).min(NOISE_LEN - 1)],
+            };
+
+            samples.push((wave * envelope * self.volume).clamp(-1.0, 1.0));
+        }
+
+        samples
+    }
+
+    /// Synthesize the sound into an asset that can be played like a loaded file
+    pub fn to_audio_source(&self) -> AudioSource {
+        AudioSource { bytes: encode_wav(&self.synthesize(SAMPLE_RATE), SAMPLE_RATE).into() }
+    }
+}
+
+/// Wrap mono samples in a 16-bit PCM WAV file, which Bevy's audio decoder understands
+pub fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // Bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // Bytes per frame
+    wav.extend_from_slice(&16u16.to_le_bytes()); // Bits per sample
+
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+
+    wav
+}
+
+/// A tiny deterministic random number generator (SplitMix64), so seeded sounds
+/// are the same on every machine and every run
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value from 0.0 up to (but not including) 1.0
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_has_expected_length_and_range() {
+        let samples = SfxParams::tone(440.0, 0.5, Waveform::Sine).synthesize(SAMPLE_RATE);
+        assert_eq!(samples.len(), (0.5 * SAMPLE_RATE as f32).round() as usize);
+        assert!(samples.iter().all(|s| (-1.0..=1.0).contains(s)));
+    }
+
+    #[test]
+    fn sine_tone_crosses_zero_at_its_frequency() {
+        let samples = SfxParams::tone(100.0, 1.0, Waveform::Sine).synthesize(SAMPLE_RATE);
+        let rising = samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        assert!((98..=101).contains(&rising), "{rising} rising zero crossings");
+    }
+
+    #[test]
+    fn seeded_sounds_are_deterministic() {
+        assert_eq!(SfxParams::pickup(7), SfxParams::pickup(7));
+        assert_eq!(SfxParams::explosion(7).synthesize(SAMPLE_RATE), SfxParams::explosion(7).synthesize(SAMPLE_RATE));
+        assert_ne!(SfxParams::explosion(7).synthesize(SAMPLE_RATE), SfxParams::explosion(8).synthesize(SAMPLE_RATE));
+    }
+
+    #[test]
+    fn equal_settings_share_a_key() {
+        assert!(SfxParams::pickup(3).key() == SfxParams::pickup(3).key());
+        assert!(SfxParams::pickup(3).key() != SfxParams::pickup(4).key());
+        assert!(SfxParams::tone(440.0, 0.5, Waveform::Sine).key() != SfxParams::tone(440.0, 0.5, Waveform::Square).key());
+    }
+
+    #[test]
+    fn wav_header_matches_samples() {
+        let samples = SfxParams::pickup(1).synthesize(SAMPLE_RATE);
+        let wav = encode_wav(&samples, SAMPLE_RATE);
+
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()) as usize, samples.len() * 2);
+        assert_eq!(wav.len(), 44 + samples.len() * 2);
+    }
+
+    #[test]
+    fn synthesized_sounds_decode() {
+        use bevy::audio::Decodable;
+
+        let samples = SfxParams::tone(440.0, 0.25, Waveform::Sine).synthesize(SAMPLE_RATE);
+        let source = SfxParams::tone(440.0, 0.25, Waveform::Sine).to_audio_source();
+        assert_eq!(source.decoder().count(), samples.len());
+    }
+}
@@ -1,3 +1,5 @@
+use bevy::window::VideoMode;
+
 /// Represents a physical display monitor.
 #[derive(Clone, Debug)]
 pub struct MonitorInfo {
@@ -6,6 +8,8 @@ pub struct MonitorInfo {
     pub height: u32,
     pub refresh_rate: f32,
     pub scale_factor: f64,
+    /// Resolutions and refresh rates the monitor supports, for exclusive fullscreen
+    pub video_modes: Vec<VideoMode>,
 }
 
 /// Provides access to system information.
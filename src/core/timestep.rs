@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+/// Upper bound on fixed steps per frame, so a long stall can't snowball into ever longer frames.
+const MAX_STEPS_PER_FRAME: u32 = 8;
+
+#[derive(Resource, Default)]
+pub struct FixedTimestep {
+    step: Option<f32>, // Seconds per fixed update, None when disabled
+
+    // Internal counters
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new(hz: f32) -> Self {
+        Self {
+            step: (hz > 0.0).then(|| 1.0 / hz),
+            accumulator: 0.0,
+        }
+    }
+
+    /// Seconds per fixed update, or 0.0 if fixed updates are disabled
+    pub fn step(&self) -> f32 {
+        self.step.unwrap_or(0.0)
+    }
+
+    /// Add the frame time to the accumulator and return how many fixed updates are due
+    pub fn advance(&mut self, delta: f32) -> u32 {
+        let Some(step) = self.step else {
+            return 0;
+        };
+
+        self.accumulator += delta;
+
+        let mut steps = 0;
+        while self.accumulator >= step && steps < MAX_STEPS_PER_FRAME {
+            self.accumulator -= step;
+            steps += 1;
+        }
+
+        // Drop whatever time we could not catch up on
+        if steps == MAX_STEPS_PER_FRAME {
+            self.accumulator = self.accumulator.min(step);
+        }
+
+        steps
+    }
+
+    /// How far we are between the last fixed update and the next one (0.0 to 1.0)
+    pub fn alpha(&self) -> f32 {
+        match self.step {
+            Some(step) => (self.accumulator / step).clamp(0.0, 1.0),
+            None => 1.0,
+        }
+    }
+}
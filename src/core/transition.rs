@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::camera::CameraMode;
+use crate::context::DrawContext;
+use crate::core::scene::{first_visible, Scene};
+
+/// Layer used for the transition overlay. Sits above the layers scenes normally use.
+pub const TRANSITION_LAYER: usize = 31;
+
+/// The visual effect used when changing scenes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    /// Fade out to a solid color, then fade in to the new scene
+    Fade { color: Color, secs: f32 },
+
+    /// Wipe a solid color panel across the screen, swapping scenes once it covers it
+    Slide { direction: SlideDirection, color: Color, secs: f32 },
+
+    /// Blend the outgoing scene into the incoming one
+    Crossfade { secs: f32 },
+}
+
+impl Transition {
+    /// Length of the transition in seconds
+    pub fn secs(&self) -> f32 {
+        match *self {
+            Transition::Fade { secs, .. } => secs,
+            Transition::Slide { secs, .. } => secs,
+            Transition::Crossfade { secs } => secs,
+        }
+    }
+}
+
+/// The direction a `Transition::Slide` panel travels in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SlideDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// A transition in progress, owned by the SceneManager
+pub(crate) struct ActiveTransition {
+    pub effect: Transition,
+    pub elapsed: f32,
+
+    /// The scene being left behind (Switch and Pop). It is drawn but no longer updated.
+    pub outgoing: Option<Box<dyn Scene>>,
+
+    /// True if the top of the stack is the incoming scene (Switch and Push)
+    pub incoming_on_top: bool,
+}
+
+impl ActiveTransition {
+    /// Progress from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        let secs = self.effect.secs();
+        if secs <= 0.0 { 1.0 } else { (self.elapsed / secs).min(1.0) }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    /// Draw the stack with the effect applied
+    pub fn draw(&mut self, stack: &mut [Box<dyn Scene>], ctx: &mut DrawContext) {
+        let t = self.progress();
+
+        // Scenes that look the same before and after the transition
+        let common = if self.incoming_on_top { stack.len().saturating_sub(1) } else { stack.len() };
+        let (base, top) = stack.split_at_mut(common);
+
+        let start = first_visible(base);
+        for scene in base[start..].iter_mut() {
+            scene.draw(ctx);
+        }
+
+        match self.effect {
+            Transition::Fade { color, .. } => {
+                self.draw_front(top, t < 0.5, ctx);
+                let alpha = 1.0 - (2.0 * t - 1.0).abs();
+                draw_panel(ctx, Vec2::ZERO, color.with_alpha(color.alpha() * alpha));
+            }
+            Transition::Slide { direction, color, .. } => {
+                self.draw_front(top, t < 0.5, ctx);
+                let travel = 1.0 - 2.0 * t;
+                let size = ctx.screen_size();
+                let offset = match direction {
+                    SlideDirection::Left => vec2(size.x * travel, 0.0),
+                    SlideDirection::Right => vec2(-size.x * travel, 0.0),
+                    SlideDirection::Up => vec2(0.0, -size.y * travel),
+                    SlideDirection::Down => vec2(0.0, size.y * travel),
+                };
+                draw_panel(ctx, offset, color);
+            }
+            Transition::Crossfade { .. } => {
+                // A new scene on top of an unchanged stack fades in by itself
+                let incoming_alpha = if self.outgoing.is_some() { 1.0 } else { t };
+                for scene in top.iter_mut() {
+                    draw_faded(scene.as_mut(), incoming_alpha, ctx);
+                }
+                if let Some(outgoing) = self.outgoing.as_mut() {
+                    draw_faded(outgoing.as_mut(), 1.0 - t, ctx);
+                }
+            }
+        }
+    }
+
+    /// Draw whichever scene should be in front: the outgoing one before the swap, the incoming one after
+    fn draw_front(&mut self, top: &mut [Box<dyn Scene>], before_swap: bool, ctx: &mut DrawContext) {
+        if before_swap {
+            if let Some(outgoing) = self.outgoing.as_mut() {
+                outgoing.draw(ctx);
+            }
+        } else {
+            for scene in top.iter_mut() {
+                scene.draw(ctx);
+            }
+        }
+    }
+}
+
+/// Draw a scene and scale the alpha of everything it queued
+fn draw_faded(scene: &mut dyn Scene, alpha: f32, ctx: &mut DrawContext) {
+    let start = ctx.graphics_queue.0.len();
+    scene.draw(ctx);
+    for cmd in ctx.graphics_queue.0[start..].iter_mut() {
+        cmd.fade(alpha);
+    }
+}
+
+/// Cover the screen with a solid color on the transition layer
+fn draw_panel(ctx: &mut DrawContext, offset: Vec2, color: Color) {
+    let size = ctx.screen_size();
+    ctx.with_layer(TRANSITION_LAYER, |overlay| {
+        overlay.set_camera(CameraMode::default());
+        overlay.draw2d.rect(offset, size, None, color);
+    });
+}
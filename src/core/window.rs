@@ -1,8 +1,50 @@
+use std::path::PathBuf;
 use bevy::prelude::*;
-use bevy::window::WindowMode;
+use bevy::render::render_resource::TextureFormat;
+use bevy::window::{CursorGrabMode, CursorOptions, PresentMode, PrimaryWindow, VideoMode, VideoModeSelection, WindowMode, WindowPosition};
+use bevy::winit::WinitWindows;
+
+/// Stand-in for the primary window when running headless.
+/// It is never attached to an OS window, so changes to it have no visible effect.
+#[derive(Resource)]
+pub struct HeadlessWindow {
+    pub window: Window,
+    pub cursor: CursorOptions,
+}
+
+impl HeadlessWindow {
+    pub fn new(window: Window, cursor: CursorOptions) -> Self {
+        Self { window, cursor }
+    }
+}
+
+/// An icon waiting to be shown in the title bar and taskbar, until its image has loaded
+#[derive(Resource, Default)]
+pub struct WindowIcon {
+    pub(crate) pending: Option<Handle<Image>>,
+}
+
+/// Something that happened to the window since the last frame
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowEvent {
+    /// The window was resized to this logical size
+    Resized { width: f32, height: f32 },
+    FocusGained,
+    FocusLost,
+    /// The window moved to a monitor with a different DPI, or the OS scale setting changed
+    ScaleFactorChanged(f64),
+    /// The player clicked the close button. See `WindowContext::set_close_veto`.
+    CloseRequested,
+    /// A file was dragged onto the window and dropped
+    FileDropped(PathBuf),
+}
 
 pub struct WindowContext<'a> {
     pub(crate) window: &'a mut Window,
+    pub(crate) cursor: &'a mut CursorOptions,
+    pub(crate) events: Vec<WindowEvent>,
+    pub(crate) close_veto: &'a mut bool,
+    pub(crate) icon: &'a mut WindowIcon,
 }
 
 impl<'a> WindowContext<'a> {
@@ -11,6 +53,12 @@ impl<'a> WindowContext<'a> {
         self.window.title = title.to_string();
     }
 
+    /// Show an image in the title bar and taskbar, e.g. `ctx.window.set_icon(ctx.load_image("icon.png"))`.
+    /// The icon changes once the image has loaded. Some platforms (e.g. Wayland and macOS) ignore it.
+    pub fn set_icon(&mut self, image: Handle<Image>) {
+        self.icon.pending = Some(image);
+    }
+
     /// Resize the window
     pub fn set_size(&mut self, width: u32, height: u32) {
         self.window.resolution.set(width as f32, height as f32);
@@ -30,8 +78,138 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    /// Switch to exclusive fullscreen on a monitor, changing its resolution to `video_mode`
+    /// (one of the monitor's `video_modes` from `ctx.system.monitors()`), or keeping its current mode if `None`.
+    pub fn set_exclusive_fullscreen(&mut self, monitor: MonitorSelection, video_mode: Option<VideoMode>) {
+        let video_mode = video_mode.map(VideoModeSelection::Specific).unwrap_or(VideoModeSelection::Current);
+        self.window.mode = WindowMode::Fullscreen(monitor, video_mode);
+    }
+
+    /// Move the window so its top left corner is at this position on the desktop, in physical pixels
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.window.position = WindowPosition::At(IVec2::new(x, y));
+    }
+
+    /// Center the window on its current monitor
+    pub fn center(&mut self) {
+        self.window.position = WindowPosition::Centered(MonitorSelection::Current);
+    }
+
+    /// Get the window position, if it is known
+    pub fn position(&self) -> Option<IVec2> {
+        match self.window.position {
+            WindowPosition::At(position) => Some(position),
+            _ => None,
+        }
+    }
+
+    /// Choose how frames are presented, e.g. `PresentMode::AutoNoVsync` to turn vsync off
+    /// or `PresentMode::Mailbox` for low latency without tearing
+    pub fn set_present_mode(&mut self, mode: PresentMode) {
+        self.window.present_mode = mode;
+    }
+
+    /// Turn vsync on or off
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.set_present_mode(if vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync });
+    }
+
+    /// Get the current present mode
+    pub fn present_mode(&self) -> PresentMode {
+        self.window.present_mode
+    }
+
+    /// Allow or prevent the player resizing the window
+    pub fn set_resizable(&mut self, resizable: bool) {
+        self.window.resizable = resizable;
+    }
+
+    /// Show or hide the title bar and border
+    pub fn set_decorations(&mut self, decorations: bool) {
+        self.window.decorations = decorations;
+    }
+
+    /// Stop the player making the window smaller than this
+    pub fn set_min_size(&mut self, width: u32, height: u32) {
+        self.window.resize_constraints.min_width = width as f32;
+        self.window.resize_constraints.min_height = height as f32;
+    }
+
+    /// Minimize the window to the taskbar, or restore it
+    pub fn set_minimized(&mut self, minimized: bool) {
+        self.window.set_minimized(minimized);
+    }
+
+    /// Maximize the window, or restore it
+    pub fn set_maximized(&mut self, maximized: bool) {
+        self.window.set_maximized(maximized);
+    }
+
+    /// Show or hide the mouse cursor while it is over the window
+    pub fn set_cursor_visible(&mut self, visible: bool) {
+        self.cursor.visible = visible;
+    }
+
+    /// Keep the cursor inside the window (`Confined`), lock it in place (`Locked`), or let it go (`None`).
+    /// Locking is handy for mouse look; read movement with `ctx.input.mouse_delta()`.
+    pub fn set_cursor_grab(&mut self, mode: CursorGrabMode) {
+        self.cursor.grab_mode = mode;
+    }
+
+    /// Enable IME so players can type text in languages like Japanese or Chinese.
+    /// Committed text shows up in `ctx.input.text_typed()`.
+    pub fn set_ime_enabled(&mut self, enabled: bool) {
+        self.window.ime_enabled = enabled;
+    }
+
+    /// Get everything that happened to the window since the last frame
+    pub fn events(&self) -> &[WindowEvent] {
+        &self.events
+    }
+
+    /// Returns true if the player tried to close the window this frame
+    pub fn close_requested(&self) -> bool {
+        self.events.contains(&WindowEvent::CloseRequested)
+    }
+
+    /// Stop the close button from quitting, e.g. to ask "Save before quitting?".
+    /// Close requests still show up in `events()`; quit with `SceneTransition::Quit` when ready.
+    /// Stays in effect until turned off.
+    pub fn set_close_veto(&mut self, veto: bool) {
+        *self.close_veto = veto;
+    }
+
     /// Check if the window is currently focused
     pub fn is_focused(&self) -> bool {
         self.window.focused
     }
-}
\ No newline at end of file
+}
+
+/// Hand a pending icon to the OS window once its image has loaded
+pub fn apply_window_icon(mut icon: ResMut<WindowIcon>, images: Res<Assets<Image>>, asset_server: Res<AssetServer>, winit_windows: NonSend<WinitWindows>, primary: Query<Entity, With<PrimaryWindow>>,) {
+    let Some(handle) = icon.pending.clone() else {
+        return;
+    };
+
+    if asset_server.load_state(&handle).is_failed() {
+        warn!("Could not load the window icon");
+        icon.pending = None;
+        return;
+    }
+
+    let Some(image) = images.get(&handle) else {
+        return;
+    };
+    let Some(window) = primary.single().ok().and_then(|entity| winit_windows.get_window(entity)) else {
+        return;
+    };
+    icon.pending = None;
+
+    let size = image.size();
+    let rgba = image.convert(TextureFormat::Rgba8UnormSrgb).and_then(|image| image.data);
+    match rgba.map(|rgba| winit::window::Icon::from_rgba(rgba, size.x, size.y)) {
+        Some(Ok(winit_icon)) => window.set_window_icon(Some(winit_icon)),
+        Some(Err(err)) => warn!("Could not use the window icon: {}", err),
+        None => warn!("Could not use the window icon: its pixel format can't be converted to RGBA"),
+    }
+}
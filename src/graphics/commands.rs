@@ -14,4 +14,32 @@ pub enum GraphicsCommand {
     Sprite(SpriteCommand),
     Text(TextCommand),
     Light(LightCommand),
-}
\ No newline at end of file
+}
+
+impl GraphicsCommand {
+    /// Multiply the alpha of the command's color by `alpha`.
+    /// Lights and models have no alpha and are left untouched.
+    pub fn fade(&mut self, alpha: f32) {
+        let color = match self {
+            GraphicsCommand::Geometry(cmd) => match cmd {
+                GeometryCommand::Circle { color, .. }
+                | GeometryCommand::Rect { color, .. }
+                | GeometryCommand::Line { color, .. }
+                | GeometryCommand::Ring { color, .. }
+                | GeometryCommand::Cube { color, .. }
+                | GeometryCommand::Cuboid { color, .. }
+                | GeometryCommand::Sphere { color, .. }
+                | GeometryCommand::Cylinder { color, .. }
+                | GeometryCommand::Cone { color, .. }
+                | GeometryCommand::Torus { color, .. }
+                | GeometryCommand::Plane { color, .. }
+                | GeometryCommand::Quad { color, .. } => color,
+                GeometryCommand::Model { .. } => return,
+            },
+            GraphicsCommand::Sprite(cmd) => &mut cmd.color,
+            GraphicsCommand::Text(cmd) => &mut cmd.color,
+            GraphicsCommand::Light(_) => return,
+        };
+        color.set_alpha(color.alpha() * alpha);
+    }
+}
@@ -134,28 +134,46 @@ pub enum GeometryCommand {
 pub struct Geometry2d<'a> {
     pub queue: &'a RefCell<&'a mut GraphicsQueue>,
     pub layer_id: usize,
+    /// Shapes entirely outside this world area are skipped (see `LayerContext::set_culling`)
+    pub(crate) cull: Option<Rect>,
 }
 
 impl<'a> Geometry2d<'a> {
+    fn visible(&self, bounds: Rect) -> bool {
+        self.cull.is_none_or(|view| !view.intersect(bounds).is_empty())
+    }
+
     pub fn circle(&self, position: Vec2, radius: f32, texture: Option<Handle<Image>>, color: Color) {
+        if !self.visible(Rect::from_center_half_size(position, Vec2::splat(radius))) {
+            return;
+        }
         self.queue.borrow_mut().0.push(GraphicsCommand::Geometry(
             GeometryCommand::Circle { position, radius, color, texture, layer: self.layer_id }
         ));
     }
     
     pub fn rect(&self, position: Vec2, size: Vec2, texture: Option<Handle<Image>>, color: Color) {
+        if !self.visible(Rect::from_center_size(position, size)) {
+            return;
+        }
         self.queue.borrow_mut().0.push(GraphicsCommand::Geometry(
             GeometryCommand::Rect { position, size, color, texture, layer: self.layer_id }
         ));
     }
 
     pub fn line(&self, start: Vec2, end: Vec2, thickness: f32, color: Color) {
+        if !self.visible(Rect::from_corners(start, end).inflate(thickness / 2.0)) {
+            return;
+        }
         self.queue.borrow_mut().0.push(GraphicsCommand::Geometry(
             GeometryCommand::Line { start, end, thickness, color, layer: self.layer_id }
         ));
     }
     
     pub fn ring(&self, position: Vec2, radius: f32, thickness: f32, color: Color) {
+        if !self.visible(Rect::from_center_half_size(position, Vec2::splat(radius + thickness))) {
+            return;
+        }
         self.queue.borrow_mut().0.push(GraphicsCommand::Geometry(
             GeometryCommand::Ring { position, radius, thickness, color, layer: self.layer_id }
         ));
@@ -20,6 +20,9 @@ pub struct ImmediateSprite;
 pub struct SpriteContext<'a> {
     pub queue: &'a RefCell<&'a mut GraphicsQueue>,
     pub layer_id: usize,
+    pub(crate) images: &'a Assets<Image>,
+    /// Sprites entirely outside this world area are skipped (see `LayerContext::set_culling`)
+    pub(crate) cull: Option<Rect>,
 }
 
 impl<'a> SpriteContext<'a> {
@@ -31,6 +34,14 @@ impl<'a> SpriteContext<'a> {
 
     /// Draw a sprite at the specified position with scale and color.
     pub fn draw_ext(&self, image: &Handle<Image>, x: f32, y: f32, scale: f32, color: Color) {
+        // Images still loading have no size yet, so they're always drawn
+        if let (Some(view), Some(loaded)) = (self.cull, self.images.get(image)) {
+            let bounds = Rect::from_center_size(Vec2::new(x, y), loaded.size_f32() * scale);
+            if view.intersect(bounds).is_empty() {
+                return;
+            }
+        }
+
         self.queue.borrow_mut().0.push(GraphicsCommand::Sprite(SpriteCommand {
             image: image.clone(),
             position: Vec2::new(x, y),
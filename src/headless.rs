@@ -0,0 +1,177 @@
+use std::time::Duration;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent, RawGamepadButtonChangedEvent, RawGamepadEvent};
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{FileDragAndDrop, Ime, WindowCloseRequested, WindowFocused, WindowResized, WindowScaleFactorChanged};
+
+use crate::camera::{CameraControllers, CameraQueue, LayerLenses, LayerRenderSettings, LayerTargets, LayerViewports};
+use crate::core::actions::ActionMap;
+use crate::core::audio::{ActiveLoops, ActiveSounds, AudioBuses, AudioListener, AudioQueue, SfxCache};
+use crate::core::fps::{monitor_fps, FpsResource};
+use crate::core::gamepad::GamepadId;
+use crate::core::scene::SceneManager;
+use crate::core::timestep::FixedTimestep;
+use crate::core::window::{HeadlessWindow, WindowIcon};
+use crate::graphics::commands::GraphicsQueue;
+use crate::core::replay::InputReplay;
+use crate::runner::{add_input_recording, internal_game_loop, primary_window, AppConfig};
+
+/// Simulated frame duration used when running headless (60 FPS).
+const HEADLESS_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// An app with no window, renderer or audio device, stepped one frame at a time.
+/// Time advances by a fixed step per frame so runs are reproducible.
+pub struct HeadlessApp {
+    app: App,
+}
+
+impl HeadlessApp {
+    pub(crate) fn new(mut config: AppConfig, manager: SceneManager) -> Self {
+        let mut app = App::new();
+
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), InputPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(HEADLESS_FRAME_TIME))
+            // IME and window messages come from the window plugin, which headless mode leaves out
+            .add_message::<Ime>()
+            .add_message::<WindowResized>()
+            .add_message::<WindowFocused>()
+            .add_message::<WindowScaleFactorChanged>()
+            .add_message::<WindowCloseRequested>()
+            .add_message::<FileDragAndDrop>()
+            // Scenes load assets through the AssetServer, which panics on unregistered types
+            .init_asset::<Image>()
+            .init_asset::<Font>()
+            .init_asset::<Mesh>()
+            .init_asset::<bevy::prelude::Scene>()
+            .init_asset::<AudioSource>()
+            .init_resource::<FpsResource>()
+            .init_resource::<ActionMap>()
+            .insert_resource(config.gamepad_config)
+            .insert_resource(config.fixed_hz.map(FixedTimestep::new).unwrap_or_default())
+            .insert_resource(GraphicsQueue::default())
+            .insert_resource(AudioQueue::default())
+            .insert_resource(ActiveLoops::default())
+            .init_resource::<AudioBuses>()
+            .init_resource::<ActiveSounds>()
+            .init_resource::<AudioListener>()
+            .init_resource::<SfxCache>()
+            .init_resource::<WindowIcon>()
+            .insert_resource(ClearColor(Color::BLACK))
+            .insert_resource(CameraQueue::default())
+            .init_resource::<LayerViewports>()
+            .init_resource::<LayerTargets>()
+            .init_resource::<LayerLenses>()
+            .init_resource::<LayerRenderSettings>()
+            .init_resource::<CameraControllers>()
+            .insert_resource({
+                let (window, cursor) = primary_window(&config);
+                HeadlessWindow::new(window, cursor)
+            })
+            .insert_non_send_resource(manager)
+            .add_systems(Update, (
+                internal_game_loop,
+                monitor_fps,
+                discard_output
+            ).chain());
+
+        if let Some(resolution) = config.virtual_resolution {
+            app.insert_resource(resolution);
+        }
+
+        add_input_recording(&mut app, &mut config);
+
+        app.finish();
+        app.cleanup();
+
+        Self { app }
+    }
+
+    /// Run a single frame. Returns false once a scene has asked to quit,
+    /// or an input replay has played every recorded frame.
+    pub fn step(&mut self) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        self.app.update();
+        !self.is_done()
+    }
+
+    fn is_done(&self) -> bool {
+        let replay_finished = self.app.world()
+            .get_resource::<InputReplay>()
+            .is_some_and(InputReplay::is_finished);
+        replay_finished || self.scenes().is_quitting()
+    }
+
+    /// Run up to `frames` frames, stopping early if a scene quits.
+    pub fn run(&mut self, frames: u32) {
+        for _ in 0..frames {
+            if !self.step() {
+                break;
+            }
+        }
+    }
+
+    /// Run until a scene returns `SceneTransition::Quit` or an input replay ends.
+    pub fn run_until_quit(&mut self) {
+        while self.step() {}
+    }
+
+    /// Get the scene stack to inspect game state.
+    pub fn scenes(&self) -> &SceneManager {
+        self.app.world().non_send_resource::<SceneManager>()
+    }
+
+    /// Get the scene stack mutably.
+    pub fn scenes_mut(&mut self) -> Mut<'_, SceneManager> {
+        self.app.world_mut().non_send_resource_mut::<SceneManager>()
+    }
+
+    /// Consume the app and return the scene stack.
+    pub fn into_scenes(mut self) -> SceneManager {
+        self.app.world_mut()
+            .remove_non_send_resource::<SceneManager>()
+            .expect("SceneManager is inserted when the app is built")
+    }
+
+    /// Access the underlying Bevy app, e.g. to write input messages directly.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// Plug in a synthetic gamepad. It shows up in `ctx.input.gamepads()` on the next `step()`,
+    /// along with any buttons and axes set before it.
+    pub fn connect_gamepad(&mut self, name: &str) -> GamepadId {
+        let entity = self.app.world_mut().spawn_empty().id();
+        self.app.world_mut().write_message(GamepadConnectionEvent::new(entity, GamepadConnection::Connected {
+            name: name.to_string(),
+            vendor_id: None,
+            product_id: None,
+        }));
+        GamepadId(entity)
+    }
+
+    /// Unplug a synthetic gamepad.
+    pub fn disconnect_gamepad(&mut self, id: GamepadId) {
+        self.app.world_mut().write_message(GamepadConnectionEvent::new(id.0, GamepadConnection::Disconnected));
+    }
+
+    /// Set a gamepad button's value (0.0 released, 1.0 fully pressed) from the next `step()`.
+    pub fn set_gamepad_button(&mut self, id: GamepadId, button: GamepadButton, value: f32) {
+        self.app.world_mut().write_message(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(id.0, button, value)));
+    }
+
+    /// Set a gamepad axis value (-1.0 to 1.0) from the next `step()`.
+    pub fn set_gamepad_axis(&mut self, id: GamepadId, axis: GamepadAxis, value: f32) {
+        self.app.world_mut().write_message(RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(id.0, axis, value)));
+    }
+}
+
+/// Headless mode has nothing to render or play, so queued output is dropped each frame.
+fn discard_output(mut graphics: ResMut<GraphicsQueue>, mut audio: ResMut<AudioQueue>, mut sounds: ResMut<ActiveSounds>, mut cameras: ResMut<CameraQueue>) {
+    graphics.0.clear();
+    audio.0.clear();
+    sounds.clear();
+    cameras.0.clear();
+}
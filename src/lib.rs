@@ -5,6 +5,7 @@ mod camera;
 mod context;
 mod core;
 mod graphics;
+mod headless;
 
 /// Type aliases for common asset handles
 pub type SceneAsset = Handle<Scene>;
@@ -22,16 +23,28 @@ pub type SoundAsset = Handle<bevy::prelude::AudioSource>;
 pub type TextureAsset = Handle<Image>;
 
 pub mod prelude {
-    pub use crate::core::scene::{Scene, SceneTransition, SceneManager};
+    pub use crate::core::scene::{Scene, SceneTransition, SceneManager, SceneResult};
+    pub use crate::core::transition::{Transition, SlideDirection};
+    pub use crate::core::actions::{ActionMap, InputBinding, AxisBinding};
 
     // Export Breeze types
     pub use crate::{SceneAsset, ImageAsset, FontAsset, SoundAsset};
 
     pub use crate::runner::{Breeze};
+    pub use crate::headless::HeadlessApp;
+    pub use crate::core::gamepad::GamepadId;
+    pub use crate::core::input::TouchPoint;
+    pub use crate::core::window::WindowEvent;
+    pub use crate::core::replay::{InputFrame, InputRecording};
+    pub use crate::core::audio::{AudioBus, SoundId};
+    pub use crate::core::synth::{SfxParams, Waveform};
     pub use crate::context::{Context, DrawContext, LayerContext};
-    pub use crate::camera::CameraMode;
+    pub use crate::camera::{Camera2dController, CameraLens, CameraMode, FlyController, LayerClear, LayerSettings, LayerView, LayerViewport, OrbitController, ScaleMode, ray_plane_intersection};
     pub use bevy::color::palettes::css::*;
 
     // basic bevy types
-    pub use bevy::prelude::{vec2, vec3, vec4, Vec2, Vec3, Vec4, Quat, Color, KeyCode, MouseButton};
+    pub use bevy::core_pipeline::tonemapping::Tonemapping;
+    pub use bevy::render::view::Msaa;
+    pub use bevy::window::{CursorGrabMode, MonitorSelection, PresentMode, VideoMode};
+    pub use bevy::prelude::{vec2, vec3, vec4, Vec2, Vec3, Vec4, Quat, Rect, Ray3d, Color, KeyCode, MouseButton, GamepadButton, GamepadAxis};
 }
\ No newline at end of file
@@ -1,30 +1,56 @@
+use std::path::PathBuf;
 use crate::core::scene::Scene;
-use bevy::camera::visibility::RenderLayers;
 use bevy::diagnostic::{EntityCountDiagnosticsPlugin, FrameCount, FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
 use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonState;
+use bevy::input::gamepad::GamepadConnectionEvent;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll};
+use bevy::input::touch::Touches;
 use bevy::prelude::*;
 use bevy::render::renderer::RenderAdapterInfo;
-use bevy::window::{PresentMode, PrimaryWindow};
+use bevy::window::{CursorGrabMode, CursorOptions, FileDragAndDrop, Ime, PresentMode, PrimaryWindow, VideoMode, VideoModeSelection, WindowCloseRequested, WindowFocused, WindowMode, WindowPosition, WindowResizeConstraints, WindowResized, WindowScaleFactorChanged};
 use bevy::winit::WinitWindows;
-use crate::core::audio::{play_audio, ActiveLoops, AudioContext, AudioQueue};
-use crate::camera::{manage_cameras, CameraQueue};
+use crate::core::actions::ActionMap;
+use crate::core::audio::{apply_bus_volumes, play_audio, sync_loops, update_listener, update_sounds, ActiveLoops, ActiveSounds, AudioBuses, AudioContext, AudioListener, AudioQueue, SfxCache};
+use crate::camera::{apply_viewports, manage_cameras, CameraControllers, CameraQueue, LayerLenses, LayerRenderSettings, LayerTargets, LayerViewItem, LayerView, LayerViewports, LayerViews, LetterboxCamera, ScaleMode, VirtualResolution};
 use crate::context::{Context, DrawContext};
 use crate::core::fps::{monitor_fps, FpsResource};
-use crate::core::input::InputContext;
+use crate::core::gamepad::{ConnectedGamepad, GamepadConfig, GamepadId};
+use crate::core::input::{InputContext, TouchPoint};
+use crate::core::replay::{InputFrame, InputRecorder, InputRecording, InputReplay};
 use crate::core::scene::SceneManager;
 use crate::core::system::{MonitorInfo, SystemContext};
-use crate::core::window::WindowContext;
+use crate::core::timestep::FixedTimestep;
+use crate::core::window::{apply_window_icon, HeadlessWindow, WindowContext, WindowEvent, WindowIcon};
+use crate::headless::HeadlessApp;
 
 use crate::graphics::commands::GraphicsQueue;
 use crate::graphics::renderer::render_graphics;
 use crate::graphics::geometry::{GlobalGeometryResources, MaterialCache};
 
+
 pub struct AppConfig {
     pub title: String,
     pub width: u32,
     pub height: u32,
     pub enable_logging: bool,
     pub enable_diagnostics: bool,
+    pub headless: bool,
+    pub fixed_hz: Option<f32>,
+    pub gamepad_config: GamepadConfig,
+    pub record_path: Option<PathBuf>,
+    pub replay: Option<InputRecording>,
+    pub present_mode: PresentMode,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub min_size: Option<(u32, u32)>,
+    pub position: Option<IVec2>,
+    pub cursor_visible: bool,
+    pub cursor_grab: CursorGrabMode,
+    pub virtual_resolution: Option<VirtualResolution>,
+    pub mode: WindowMode,
+    pub icon: Option<String>,
 }
 
 impl Default for AppConfig {
@@ -35,6 +61,21 @@ impl Default for AppConfig {
             height: 600,
             enable_logging: false,
             enable_diagnostics: false,
+            headless: false,
+            fixed_hz: None,
+            gamepad_config: GamepadConfig::default(),
+            record_path: None,
+            replay: None,
+            present_mode: PresentMode::AutoVsync,
+            resizable: true,
+            decorations: true,
+            min_size: None,
+            position: None,
+            cursor_visible: true,
+            cursor_grab: CursorGrabMode::None,
+            virtual_resolution: None,
+            mode: WindowMode::Windowed,
+            icon: None,
         }
     }
 }
@@ -77,11 +118,129 @@ impl Breeze {
         self
     }
 
+    /// Call `Scene::fixed_update` at a fixed rate (in updates per second),
+    /// independent of the frame rate.
+    pub fn fixed_timestep(mut self, hz: f32) -> Self {
+        self.config.fixed_hz = Some(hz);
+        self
+    }
+
+    /// Set the gamepad stick and trigger dead zones (0.0 to 1.0).
+    pub fn gamepad_deadzones(mut self, stick: f32, trigger: f32) -> Self {
+        self.config.gamepad_config.stick_deadzone = stick.clamp(0.0, 1.0);
+        self.config.gamepad_config.trigger_deadzone = trigger.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Choose how frames are presented (defaults to `PresentMode::AutoVsync`).
+    pub fn present_mode(mut self, mode: PresentMode) -> Self {
+        self.config.present_mode = mode;
+        self
+    }
+
+    /// Allow or prevent the player resizing the window.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.config.resizable = resizable;
+        self
+    }
+
+    /// Start in exclusive fullscreen on a monitor, switching it to `video_mode`
+    /// (one of the monitor's `video_modes`), or keeping its current mode if `None`.
+    pub fn fullscreen(mut self, monitor: MonitorSelection, video_mode: Option<VideoMode>) -> Self {
+        let video_mode = video_mode.map(VideoModeSelection::Specific).unwrap_or(VideoModeSelection::Current);
+        self.config.mode = WindowMode::Fullscreen(monitor, video_mode);
+        self
+    }
+
+    /// Show this image in the title bar and taskbar, e.g. `.icon("icon.png")`.
+    pub fn icon(mut self, path: &str) -> Self {
+        self.config.icon = Some(path.to_string());
+        self
+    }
+
+    /// Show or hide the title bar and border.
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.config.decorations = decorations;
+        self
+    }
+
+    /// Stop the player making the window smaller than this.
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.config.min_size = Some((width, height));
+        self
+    }
+
+    /// Open the window with its top left corner at this desktop position, in physical pixels.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.config.position = Some(IVec2::new(x, y));
+        self
+    }
+
+    /// Show or hide the mouse cursor over the window.
+    pub fn cursor_visible(mut self, visible: bool) -> Self {
+        self.config.cursor_visible = visible;
+        self
+    }
+
+    /// Confine or lock the cursor to the window from the start.
+    pub fn cursor_grab(mut self, mode: CursorGrabMode) -> Self {
+        self.config.cursor_grab = mode;
+        self
+    }
+
+    /// Design the game at a fixed resolution (e.g. 320x180 for pixel art) and scale it to fit the window.
+    /// 2D layer cameras show `width` x `height` world units, and `mouse_pos` reports positions in those units.
+    pub fn virtual_resolution(mut self, width: u32, height: u32, mode: ScaleMode) -> Self {
+        self.config.virtual_resolution = Some(VirtualResolution {
+            width: width as f32,
+            height: height as f32,
+            mode,
+        });
+        self
+    }
+
+    /// Record every frame's input and frame time to a file, for reproducing bugs later.
+    pub fn record_input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.record_path = Some(path.into());
+        self
+    }
+
+    /// Play back a recording instead of reading live input.
+    /// Each frame uses the recorded frame time, so the scenes see exactly what happened when it was recorded.
+    /// Only the keyboard and mouse are recorded, so gamepads and touches are hidden until the replay ends.
+    pub fn replay_input(mut self, recording: InputRecording) -> Self {
+        self.config.replay = Some(recording);
+        self
+    }
+
+    /// Run without a window, GPU or audio device (e.g. on CI machines).
+    /// Scenes are still initialised, updated and drawn, but nothing is presented.
+    pub fn headless(mut self) -> Self {
+        self.config.headless = true;
+        self
+    }
+
     /// Consumes the builder and runs the game.
     pub fn run(self, initial_scene: impl Scene + 'static) {
         let manager = SceneManager::new(initial_scene);
         run(self.config, manager);
     }
+
+    /// Consumes the builder and runs the game headless for at most `frames` frames,
+    /// stopping early if a scene returns `SceneTransition::Quit`.
+    /// Returns the final `SceneManager` so the game state can be inspected.
+    pub fn run_headless(self, initial_scene: impl Scene + 'static, frames: u32) -> SceneManager {
+        let mut app = self.build_headless(initial_scene);
+        app.run(frames);
+        app.into_scenes()
+    }
+
+    /// Consumes the builder and returns a headless app that is stepped by hand,
+    /// so tests can inject input between frames.
+    pub fn build_headless(self, initial_scene: impl Scene + 'static) -> HeadlessApp {
+        let manager = SceneManager::new(initial_scene);
+        HeadlessApp::new(self.config, manager)
+    }
 }
 
 impl Default for Breeze {
@@ -93,7 +252,34 @@ impl Default for Breeze {
 }
 
 #[derive(Default)]
-pub struct InternalState { initialized: bool }
+pub struct InternalState { initialized: bool, close_veto: bool }
+
+/// Messages about the window that are passed on to scenes as `WindowEvent`s
+#[derive(SystemParam)]
+pub struct WindowMessages<'w, 's> {
+    resized: MessageReader<'w, 's, WindowResized>,
+    focused: MessageReader<'w, 's, WindowFocused>,
+    scale_factor: MessageReader<'w, 's, WindowScaleFactorChanged>,
+    close_requested: MessageReader<'w, 's, WindowCloseRequested>,
+    file_drag_and_drop: MessageReader<'w, 's, FileDragAndDrop>,
+}
+
+impl WindowMessages<'_, '_> {
+    fn read(&mut self) -> Vec<WindowEvent> {
+        let mut events = Vec::new();
+
+        events.extend(self.resized.read().map(|e| WindowEvent::Resized { width: e.width, height: e.height }));
+        events.extend(self.focused.read().map(|e| if e.focused { WindowEvent::FocusGained } else { WindowEvent::FocusLost }));
+        events.extend(self.scale_factor.read().map(|e| WindowEvent::ScaleFactorChanged(e.scale_factor)));
+        events.extend(self.close_requested.read().map(|_| WindowEvent::CloseRequested));
+        events.extend(self.file_drag_and_drop.read().filter_map(|e| match e {
+            FileDragAndDrop::DroppedFile { path_buf, .. } => Some(WindowEvent::FileDropped(path_buf.clone())),
+            _ => None,
+        }));
+
+        events
+    }
+}
 
 #[derive(SystemParam)]
 pub struct EngineContext<'w, 's> {
@@ -101,22 +287,50 @@ pub struct EngineContext<'w, 's> {
     pub asset_server: Res<'w, AssetServer>,
 
     pub fps: Res<'w, FpsResource>,
+    pub fixed_timestep: ResMut<'w, FixedTimestep>,
 
     // Queues
     pub camera_queue: ResMut<'w, CameraQueue>,
+    pub layer_viewports: ResMut<'w, LayerViewports>,
+    pub layer_targets: ResMut<'w, LayerTargets>,
+    pub layer_lenses: ResMut<'w, LayerLenses>,
+    pub layer_settings: ResMut<'w, LayerRenderSettings>,
     pub audio_queue: ResMut<'w, AudioQueue>,
+    pub audio_buses: ResMut<'w, AudioBuses>,
+    pub active_sounds: ResMut<'w, ActiveSounds>,
+    pub active_loops: ResMut<'w, ActiveLoops>,
+    pub audio_listener: ResMut<'w, AudioListener>,
+    pub sfx_cache: ResMut<'w, SfxCache>,
+    pub window_icon: ResMut<'w, WindowIcon>,
     pub graphics_queue: ResMut<'w, GraphicsQueue>,
 
     pub keys: Res<'w, ButtonInput<KeyCode>>,
     pub mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    pub q_gamepads: Query<'w, 's, (Entity, &'static Gamepad, Option<&'static Name>)>,
+    pub gamepad_config: ResMut<'w, GamepadConfig>,
+    pub gamepad_events: MessageReader<'w, 's, GamepadConnectionEvent>,
+    pub keyboard_events: MessageReader<'w, 's, KeyboardInput>,
+    pub ime_events: MessageReader<'w, 's, Ime>,
+    pub mouse_motion: Res<'w, AccumulatedMouseMotion>,
+    pub mouse_scroll: Res<'w, AccumulatedMouseScroll>,
+    pub touches: Res<'w, Touches>,
+    pub recorder: Option<ResMut<'w, InputRecorder>>,
+    pub replay: Option<ResMut<'w, InputReplay>>,
+    pub actions: ResMut<'w, ActionMap>,
 
     pub renderer_info: Option<Res<'w, RenderAdapterInfo>>,
     pub frame_count: Option<Res<'w, FrameCount>>,
 
-    pub q_window: Query<'w, 's, &'static mut Window, With<PrimaryWindow>>,
-    pub q_camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform, Option<&'static RenderLayers>), With<Camera>>,
+    pub q_window: Query<'w, 's, (&'static mut Window, &'static mut CursorOptions), With<PrimaryWindow>>,
+    pub headless_window: Option<ResMut<'w, HeadlessWindow>>,
+    pub window_messages: WindowMessages<'w, 's>,
+    pub app_exit: MessageWriter<'w, AppExit>,
+    pub q_camera: Query<'w, 's, LayerViewItem, Without<LetterboxCamera>>,
+    pub images: Res<'w, Assets<Image>>,
 
     pub clear_color: ResMut<'w, ClearColor>,
+    pub camera_controllers: ResMut<'w, CameraControllers>,
+    pub virtual_resolution: Option<Res<'w, VirtualResolution>>,
 }
 
 pub fn internal_game_loop(mut manager: NonSendMut<SceneManager>, mut engine: EngineContext, mut state: Local<InternalState>, winit_windows: Option<NonSend<WinitWindows>>,) {
@@ -125,6 +339,9 @@ pub fn internal_game_loop(mut manager: NonSendMut<SceneManager>, mut engine: Eng
         return;
     }
 
+    // Borrow through a plain reference so fields can be borrowed separately
+    let state = &mut *state;
+
     let mut monitor_list = Vec::new();
 
     if let Some(winit) = &winit_windows {
@@ -145,6 +362,13 @@ pub fn internal_game_loop(mut manager: NonSendMut<SceneManager>, mut engine: Eng
                     height: size.height,
                     refresh_rate,
                     scale_factor: monitor.scale_factor(),
+                    video_modes: monitor.video_modes()
+                        .map(|mode| VideoMode {
+                            physical_size: UVec2::new(mode.size().width, mode.size().height),
+                            bit_depth: mode.bit_depth(),
+                            refresh_rate_millihertz: mode.refresh_rate_millihertz(),
+                        })
+                        .collect(),
                 });
             }
         }
@@ -158,6 +382,7 @@ pub fn internal_game_loop(mut manager: NonSendMut<SceneManager>, mut engine: Eng
             height: 0,
             refresh_rate: 0.0,
             scale_factor: 1.0,
+            video_modes: Vec::new(),
         });
     }
 
@@ -171,88 +396,256 @@ pub fn internal_game_loop(mut manager: NonSendMut<SceneManager>, mut engine: Eng
     // Extract Frame Count
     let frame_count = engine.frame_count.map(|f| f.0).unwrap_or(0);
 
-    let mut primary_window_result = engine.q_window.single_mut();
+    // Fall back to the detached stub window when running headless
+    let mut primary_window = engine.q_window.single_mut().ok();
+    let (window, cursor): (&mut Window, &mut CursorOptions) = if let Some((ref mut window, ref mut cursor)) = primary_window {
+        (window, cursor)
+    } else if let Some(ref mut stub) = engine.headless_window {
+        let stub = &mut **stub;
+        (&mut stub.window, &mut stub.cursor)
+    } else {
+        return;
+    };
 
-    if let Ok(ref mut window) = primary_window_result {
+    let mut gamepads_connected = Vec::new();
+    let mut gamepads_disconnected = Vec::new();
+    for event in engine.gamepad_events.read() {
+        if event.connected() {
+            gamepads_connected.push(GamepadId(event.gamepad));
+        } else {
+            gamepads_disconnected.push(GamepadId(event.gamepad));
+        }
+    }
 
-        let mut cursor_world_pos = Vec2::ZERO;
-        let target_layer_id = 0;
+    let window_size = Vec2::new(window.resolution.width(), window.resolution.height());
+    let screen_size = match &engine.virtual_resolution {
+        Some(resolution) => resolution.visible_size(window_size),
+        None => window_size,
+    };
 
-        // (Input handling code remains same...)
-        let input_camera = engine.q_camera.iter().find(|(_, _, layers)| {
-            match layers {
-                Some(l) => l.intersects(&RenderLayers::layer(target_layer_id)),
-                None => true,
-            }
+    let views = LayerViews(engine.q_camera.iter().filter_map(LayerView::new).collect());
+
+    // Touches are reported in the world space of layer 0, like mouse_pos()
+    let touch_camera = views.window_view(0);
+    let mut touches: Vec<TouchPoint> = engine.touches.iter()
+        .map(|touch| TouchPoint {
+            id: touch.id(),
+            screen_pos: touch.position(),
+            world_pos: touch_camera
+                .and_then(|camera| camera.screen_to_world(touch.position()))
+                .unwrap_or(Vec2::ZERO),
+            just_pressed: engine.touches.just_pressed(touch.id()),
+        })
+        .collect();
+
+    let mut cursor_screen_pos = window.cursor_position();
+    let mut mouse_delta = engine.mouse_motion.delta;
+    let mut mouse_wheel = engine.mouse_scroll.delta;
+
+    // Collect typed characters, skipping control keys like Backspace and Enter
+    let mut text_typed = String::new();
+    for event in engine.keyboard_events.read() {
+        if event.state == ButtonState::Pressed
+            && let Some(text) = &event.text
+        {
+            text_typed.extend(text.chars().filter(|c| !c.is_control()));
+        }
+    }
+    for event in engine.ime_events.read() {
+        if let Ime::Commit { value, .. } = event {
+            text_typed.push_str(value);
+        }
+    }
+
+    // Record the live input before anything replaces it
+    if let Some(recorder) = engine.recorder.as_mut() {
+        recorder.record(&InputFrame {
+            delta: engine.time.delta(),
+            keys: engine.keys.get_pressed().copied().collect(),
+            keys_pressed: engine.keys.get_just_pressed().copied().collect(),
+            keys_released: engine.keys.get_just_released().copied().collect(),
+            mouse_buttons: engine.mouse_buttons.get_pressed().copied().collect(),
+            mouse_pressed: engine.mouse_buttons.get_just_pressed().copied().collect(),
+            mouse_released: engine.mouse_buttons.get_just_released().copied().collect(),
+            cursor: cursor_screen_pos,
+            mouse_delta,
+            mouse_wheel,
+            text: text_typed.clone(),
         });
+    }
 
-        if let Some((camera, camera_transform, _)) = input_camera {
-            if let Some(screen_pos) = window.cursor_position() {
-                if let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, screen_pos) {
-                    cursor_world_pos = world_pos;
-                }
-            }
-        }
+    // Feed recorded input and frame times to the scenes instead of live input,
+    // falling back to live input once the recording runs out
+    let replay_frame = engine.replay.as_mut().and_then(|replay| replay.advance());
+    if let Some(frame) = &replay_frame {
+        cursor_screen_pos = frame.cursor;
+        mouse_delta = frame.mouse_delta;
+        mouse_wheel = frame.mouse_wheel;
+        text_typed = frame.text.clone();
+
+        // Gamepads and touches aren't recorded, so hide them to keep the replay deterministic
+        touches.clear();
+        gamepads_connected.clear();
+        gamepads_disconnected.clear();
+    }
 
-        {
-            let mut ctx = Context {
-                time: &engine.time,
-                fps: &engine.fps,
-                input: InputContext {
-                    keys: &engine.keys,
-                    mouse_buttons: &engine.mouse_buttons,
-                    cursor_world_pos,
-                },
+    let (time, keys, mouse_buttons): (&Time, &ButtonInput<KeyCode>, &ButtonInput<MouseButton>) =
+        match engine.replay.as_deref() {
+            Some(replay) if replay_frame.is_some() => (&replay.time, &replay.keys, &replay.mouse_buttons),
+            _ => (&engine.time, &engine.keys, &engine.mouse_buttons),
+        };
+
+    {
+        let mut ctx = Context {
+            time,
+            fps: &engine.fps,
+            input: InputContext {
+                keys,
+                mouse_buttons,
+                gamepads: engine.q_gamepads.iter()
+                    .filter(|_| replay_frame.is_none())
+                    .map(|(entity, pad, name)| ConnectedGamepad { id: GamepadId(entity), pad, name })
+                    .collect(),
+                gamepads_connected,
+                gamepads_disconnected,
+                gamepad_config: &mut engine.gamepad_config,
+                actions: &mut engine.actions,
+                cameras: &views,
+                cursor_screen_pos,
+                mouse_delta,
+                mouse_wheel,
+                text_typed,
+                touches,
+            },
+            asset_server: &engine.asset_server,
+            audio: AudioContext {
+                queue: &mut engine.audio_queue,
                 asset_server: &engine.asset_server,
-                audio: AudioContext {
-                    queue: &mut engine.audio_queue,
-                    asset_server: &engine.asset_server,
-                },
-                window: WindowContext {
-                    window
-                },
-                system: SystemContext {
-                    gpu_name,
-                    backend,
-                    frame_count,
-                    monitors: monitor_list,
-                },
-            };
-
-            if !state.initialized {
-                if let Some(scene) = manager.stack.last_mut() {
-                    scene.init(&mut ctx);
-                }
-                state.initialized = true;
+                sfx_cache: &mut engine.sfx_cache,
+                buses: &mut engine.audio_buses,
+                sounds: &mut engine.active_sounds,
+                loops: &mut engine.active_loops,
+                listener: &mut engine.audio_listener,
+            },
+            window: WindowContext {
+                window,
+                cursor,
+                events: engine.window_messages.read(),
+                close_veto: &mut state.close_veto,
+                icon: &mut engine.window_icon,
+            },
+            cameras: &mut engine.camera_controllers,
+            views: &views,
+            system: SystemContext {
+                gpu_name,
+                backend,
+                frame_count,
+                monitors: monitor_list,
+            },
+            fixed_delta: engine.fixed_timestep.step(),
+        };
+
+        if !state.initialized {
+            if let Some(scene) = manager.stack.last_mut() {
+                scene.init(&mut ctx);
             }
+            state.initialized = true;
+        }
 
-            manager.update(&mut ctx);
+        let steps = engine.fixed_timestep.advance(time.delta_secs());
+        for _ in 0..steps {
+            manager.fixed_update(&mut ctx);
         }
 
-        {
-            let mut draw_ctx = DrawContext {
-                time: &engine.time,
-                fps: &engine.fps,
-                graphics_queue: &mut engine.graphics_queue,
-                asset_server: &engine.asset_server,
-                clear_color: &mut engine.clear_color,
-                camera_queue: &mut engine.camera_queue,
-            };
-            manager.draw(&mut draw_ctx);
+        manager.update(&mut ctx);
+
+        // Closing the window quits like `SceneTransition::Quit`, unless a scene has vetoed it
+        if ctx.window.close_requested() && !*ctx.window.close_veto && !manager.should_quit {
+            manager.quit(&mut ctx);
         }
     }
+
+    if manager.should_quit {
+        engine.app_exit.write(AppExit::Success);
+    }
+
+    // Move controlled cameras once the scenes have updated their targets
+    engine.camera_controllers.update(time.delta_secs(), &views, screen_size);
+
+    {
+        let mut draw_ctx = DrawContext {
+            time,
+            fps: &engine.fps,
+            graphics_queue: &mut engine.graphics_queue,
+            asset_server: &engine.asset_server,
+            clear_color: &mut engine.clear_color,
+            camera_queue: &mut engine.camera_queue,
+            viewports: &mut engine.layer_viewports,
+            targets: &mut engine.layer_targets,
+            lenses: &mut engine.layer_lenses,
+            settings: &mut engine.layer_settings,
+            views: &views,
+            images: &engine.images,
+            alpha: engine.fixed_timestep.alpha(),
+            screen_size,
+        };
+        manager.draw(&mut draw_ctx);
+    }
+}
+
+/// Build the primary window and its cursor settings from the config
+pub(crate) fn primary_window(config: &AppConfig) -> (Window, CursorOptions) {
+    let mut resize_constraints = WindowResizeConstraints::default();
+    if let Some((width, height)) = config.min_size {
+        resize_constraints.min_width = width as f32;
+        resize_constraints.min_height = height as f32;
+    }
+
+    let window = Window {
+        title: config.title.clone(),
+        resolution: (config.width, config.height).into(),
+        present_mode: config.present_mode,
+        resizable: config.resizable,
+        decorations: config.decorations,
+        resize_constraints,
+        position: config.position.map(WindowPosition::At).unwrap_or_default(),
+        mode: config.mode,
+        ..default()
+    };
+
+    let cursor = CursorOptions {
+        visible: config.cursor_visible,
+        grab_mode: config.cursor_grab,
+        ..default()
+    };
+
+    (window, cursor)
+}
+
+/// Add the input recorder and replay resources if they were asked for
+pub(crate) fn add_input_recording(app: &mut App, config: &mut AppConfig) {
+    if let Some(path) = &config.record_path {
+        app.insert_resource(InputRecorder::create(path));
+    }
+    if let Some(recording) = config.replay.take() {
+        app.insert_resource(InputReplay::new(recording));
+    }
 }
 
-fn run(config: AppConfig, manager: SceneManager) {
+fn run(mut config: AppConfig, manager: SceneManager) {
+    if config.headless {
+        HeadlessApp::new(config, manager).run_until_quit();
+        return;
+    }
+
     let mut binding = App::new();
 
+    let (window, cursor) = primary_window(&config);
     let default_plugin_set = DefaultPlugins.set(WindowPlugin {
-        primary_window: Some(Window {
-            title: config.title,
-            resolution: (config.width, config.height).into(),
-            present_mode: PresentMode::AutoVsync,
-            ..default()
-        }),
+        primary_window: Some(window),
+        primary_cursor_options: Some(cursor),
+        // Close requests go to the scenes first, which can veto them
+        close_when_requested: false,
         ..default()
     });
 
@@ -261,20 +654,50 @@ fn run(config: AppConfig, manager: SceneManager) {
         .init_resource::<GlobalGeometryResources>()
         .init_resource::<MaterialCache>()
         .init_resource::<FpsResource>()
+        .init_resource::<ActionMap>()
+        .insert_resource(config.gamepad_config)
+        .insert_resource(config.fixed_hz.map(FixedTimestep::new).unwrap_or_default())
         .insert_resource(GraphicsQueue::default()) // The One Queue
         .insert_resource(AudioQueue::default())
         .insert_resource(ActiveLoops::default())
+        .init_resource::<AudioBuses>()
+        .init_resource::<ActiveSounds>()
+        .init_resource::<AudioListener>()
+        .init_resource::<SfxCache>()
+        .init_resource::<WindowIcon>()
         .insert_resource(ClearColor(Color::BLACK))
         .insert_resource(CameraQueue::default())
+        .init_resource::<LayerViewports>()
+        .init_resource::<LayerTargets>()
+        .init_resource::<LayerLenses>()
+        .init_resource::<LayerRenderSettings>()
+        .init_resource::<CameraControllers>()
         .insert_non_send_resource(manager)
         .add_systems(Update, (
             internal_game_loop,
             monitor_fps,
             render_graphics,
             play_audio,
-            manage_cameras
+            update_sounds,
+            sync_loops,
+            update_listener,
+            apply_bus_volumes,
+            manage_cameras,
+            apply_viewports,
+            apply_window_icon
         ).chain());
 
+    if let Some(resolution) = config.virtual_resolution {
+        app.insert_resource(resolution);
+    }
+
+    if let Some(path) = &config.icon {
+        let image = app.world().resource::<AssetServer>().load(path.clone());
+        app.insert_resource(WindowIcon { pending: Some(image) });
+    }
+
+    add_input_recording(app, &mut config);
+
     if config.enable_diagnostics {
         app.add_plugins((
             FrameTimeDiagnosticsPlugin::default(),
@@ -284,4 +707,4 @@ fn run(config: AppConfig, manager: SceneManager) {
     }
 
     app.run();
-}
\ No newline at end of file
+}
@@ -0,0 +1,154 @@
+use breeze::prelude::*;
+
+/// Pushes a `Child` on its third frame
+#[derive(Default)]
+struct Root {
+    frames: u32,
+}
+
+impl Scene for Root {
+    fn update(&mut self, _ctx: &mut Context) -> SceneTransition {
+        self.frames += 1;
+        if self.frames == 3 {
+            return SceneTransition::Push(Box::new(Child::default()));
+        }
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, _ctx: &mut DrawContext) {}
+}
+
+/// Quits on its fifth frame
+#[derive(Default)]
+struct Child {
+    frames: u32,
+}
+
+impl Scene for Child {
+    fn update(&mut self, _ctx: &mut Context) -> SceneTransition {
+        self.frames += 1;
+        if self.frames == 5 {
+            return SceneTransition::Quit;
+        }
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, _ctx: &mut DrawContext) {}
+}
+
+#[test]
+fn step_runs_one_frame_at_a_time() {
+    let mut app = Breeze::default().build_headless(Root::default());
+
+    assert!(app.step());
+    assert!(app.step());
+    assert_eq!(app.scenes().depth(), 1);
+    assert_eq!(app.scenes().current::<Root>().map(|root| root.frames), Some(2));
+
+    assert!(app.step());
+    assert_eq!(app.scenes().depth(), 2);
+    assert!(app.scenes().current::<Child>().is_some());
+}
+
+#[test]
+fn run_stops_at_the_frame_limit() {
+    let mut app = Breeze::default().build_headless(Root::default());
+    app.run(2);
+
+    assert!(!app.scenes().is_quitting());
+    assert_eq!(app.scenes().current::<Root>().map(|root| root.frames), Some(2));
+}
+
+#[test]
+fn run_until_quit_stops_when_a_scene_quits() {
+    let mut app = Breeze::default().build_headless(Root::default());
+    app.run_until_quit();
+
+    let scenes = app.into_scenes();
+    assert!(scenes.is_quitting());
+    assert_eq!(scenes.current::<Child>().map(|child| child.frames), Some(5));
+}
+
+#[test]
+fn quitting_stops_further_steps() {
+    let mut app = Breeze::default().build_headless(Root::default());
+    app.run(100);
+    assert!(app.scenes().is_quitting());
+
+    assert!(!app.step());
+    assert_eq!(app.scenes().current::<Child>().map(|child| child.frames), Some(5));
+}
+
+#[test]
+fn run_headless_returns_the_scene_stack() {
+    let scenes = Breeze::default().run_headless(Root::default(), 2);
+    assert_eq!(scenes.current::<Root>().map(|root| root.frames), Some(2));
+}
+
+/// What the scene saw from the gamepads on its latest frame
+#[derive(Default)]
+struct PadWatcher {
+    connected: Vec<GamepadId>,
+    gamepads: Vec<GamepadId>,
+    south_down: bool,
+    south_pressed: bool,
+    stick_x: f32,
+    jump_pressed: bool,
+    move_axis: f32,
+}
+
+impl Scene for PadWatcher {
+    fn init(&mut self, ctx: &mut Context) {
+        ctx.input.bind_action("jump", [GamepadButton::South.into()]);
+        ctx.input.bind_axis("move", [GamepadAxis::LeftStickX.into()]);
+    }
+
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        self.connected = ctx.input.gamepads_connected().to_vec();
+        self.gamepads = ctx.input.gamepads();
+        self.south_down = false;
+        self.south_pressed = false;
+        self.stick_x = 0.0;
+        for &id in &self.gamepads {
+            self.south_down |= ctx.input.gamepad_button_down(id, GamepadButton::South);
+            self.south_pressed |= ctx.input.gamepad_button_pressed(id, GamepadButton::South);
+            self.stick_x = ctx.input.gamepad_axis(id, GamepadAxis::LeftStickX);
+        }
+        self.jump_pressed = ctx.input.action_pressed("jump");
+        self.move_axis = ctx.input.axis("move");
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, _ctx: &mut DrawContext) {}
+}
+
+#[test]
+fn synthetic_gamepads_reach_the_scene() {
+    let mut app = Breeze::default().build_headless(PadWatcher::default());
+    app.step();
+
+    let pad = app.connect_gamepad("Test Pad");
+    app.set_gamepad_button(pad, GamepadButton::South, 1.0);
+    app.set_gamepad_axis(pad, GamepadAxis::LeftStickX, 1.0);
+    app.step();
+
+    let watcher = app.scenes().current::<PadWatcher>().unwrap();
+    assert_eq!(watcher.connected, vec![pad]);
+    assert_eq!(watcher.gamepads, vec![pad]);
+    assert!(watcher.south_down && watcher.south_pressed);
+    assert!(watcher.stick_x > 0.99, "{}", watcher.stick_x);
+    assert!(watcher.jump_pressed);
+    assert!(watcher.move_axis > 0.99, "{}", watcher.move_axis);
+
+    // Held buttons stay down without counting as pressed again
+    app.step();
+    let watcher = app.scenes().current::<PadWatcher>().unwrap();
+    assert!(watcher.south_down && !watcher.south_pressed && !watcher.jump_pressed);
+
+    app.set_gamepad_button(pad, GamepadButton::South, 0.0);
+    app.disconnect_gamepad(pad);
+    app.step();
+    let watcher = app.scenes().current::<PadWatcher>().unwrap();
+    assert!(watcher.gamepads.is_empty());
+    assert_eq!(watcher.move_axis, 0.0);
+}
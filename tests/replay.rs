@@ -0,0 +1,69 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::Entity;
+use breeze::prelude::*;
+
+/// Notes the frames on which Space was pressed, released or held
+#[derive(Default)]
+struct SpaceWatcher {
+    frame: u32,
+    log: Vec<(u32, bool, bool, bool)>,
+}
+
+impl Scene for SpaceWatcher {
+    fn update(&mut self, ctx: &mut Context) -> SceneTransition {
+        self.frame += 1;
+        let seen = (ctx.input.key_pressed(KeyCode::Space), ctx.input.key_released(KeyCode::Space), ctx.input.key_down(KeyCode::Space));
+        if seen != (false, false, false) {
+            self.log.push((self.frame, seen.0, seen.1, seen.2));
+        }
+        SceneTransition::None
+    }
+
+    fn draw(&mut self, _ctx: &mut DrawContext) {}
+}
+
+fn space(app: &mut HeadlessApp, state: ButtonState) {
+    app.app_mut().world_mut().write_message(KeyboardInput {
+        key_code: KeyCode::Space,
+        logical_key: Key::Space,
+        state,
+        text: None,
+        repeat: false,
+        window: Entity::PLACEHOLDER,
+    });
+}
+
+#[test]
+fn replays_show_scenes_the_same_presses() {
+    let path = std::env::temp_dir().join(format!("breeze-replay-test-{}.txt", std::process::id()));
+
+    let mut live = Breeze::default().record_input(&path).build_headless(SpaceWatcher::default());
+    live.step();
+
+    // A tap within a single frame
+    space(&mut live, ButtonState::Pressed);
+    space(&mut live, ButtonState::Released);
+    live.step();
+    live.step();
+
+    // A press held for two frames
+    space(&mut live, ButtonState::Pressed);
+    live.step();
+    live.step();
+    space(&mut live, ButtonState::Released);
+    live.step();
+    live.step();
+
+    // Dropping the app finishes the recording
+    let live_log = live.into_scenes().current::<SpaceWatcher>().unwrap().log.clone();
+    assert_eq!(live_log, vec![(2, true, true, false), (4, true, false, true), (5, false, false, true), (6, false, true, false)]);
+
+    let recording = InputRecording::load(&path).expect("the recording can be loaded");
+    std::fs::remove_file(&path).ok();
+    assert_eq!(recording.frames.len(), 7);
+
+    let mut replay = Breeze::default().replay_input(recording).build_headless(SpaceWatcher::default());
+    replay.run_until_quit();
+    assert_eq!(replay.scenes().current::<SpaceWatcher>().unwrap().log, live_log);
+}